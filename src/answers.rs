@@ -0,0 +1,287 @@
+use crate::registry::DEFAULT_YEAR;
+use crate::runner::{Outcome, RunReport};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+/// Expected answers keyed by (year, day, part), read from a small subset of TOML:
+///
+/// ```toml
+/// [2021.day_01]
+/// part1 = "1624"
+/// part2 = 1653
+/// ```
+///
+/// A section without a year, like `[day_01]`, is of the default year.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u32, u32, u32), String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AnswersError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            line => write!(f, "line {}: {}", line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers, AnswersError> {
+        let content = std::fs::read_to_string(path).map_err(|err| AnswersError {
+            line: 0,
+            message: format!("cannot read file: {}", err),
+        })?;
+        Answers::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Answers, AnswersError> {
+        let mut answers = Answers::default();
+        let mut section = None;
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |message: String| AnswersError {
+                line: line_number,
+                message,
+            };
+
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| error(format!("unterminated section '{}'", line)))?;
+                section = Some(
+                    parse_section(header.trim())
+                        .ok_or_else(|| error(format!("invalid section '{}'", header)))?,
+                );
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected 'key = value', found '{}'", line)))?;
+            let (year, day) = section
+                .ok_or_else(|| error("answer outside of a [YYYY.day_XX] section".to_string()))?;
+            let part = parse_key(key.trim(), "part")
+                .ok_or_else(|| error(format!("invalid key '{}'", key.trim())))?;
+            let value = parse_value(value.trim())
+                .ok_or_else(|| error(format!("invalid value '{}'", value.trim())))?;
+
+            if answers.answers.insert((year, day, part), value).is_some() {
+                return Err(error(format!(
+                    "duplicate answer for {} day {} part {}",
+                    year, day, part
+                )));
+            }
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, year: u32, day: u32, part: u32) -> Option<&str> {
+        self.answers.get(&(year, day, part)).map(String::as_str)
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => (),
+        }
+    }
+    line
+}
+
+/// `YYYY.day_XX`, or `day_XX` for the default year.
+fn parse_section(section: &str) -> Option<(u32, u32)> {
+    match section.split_once('.') {
+        Some((year, day)) => Some((year.parse().ok()?, parse_key(day, "day_")?)),
+        None => Some((DEFAULT_YEAR, parse_key(section, "day_")?)),
+    }
+}
+
+fn parse_key(key: &str, prefix: &str) -> Option<u32> {
+    key.strip_prefix(prefix)?.parse().ok()
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    match value.strip_prefix('"') {
+        Some(value) => value
+            .strip_suffix('"')
+            .filter(|value| !value.contains('"'))
+            .map(String::from),
+        None if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit() || c == '-') => {
+            Some(value.to_string())
+        }
+        None => None,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing { actual: String },
+    Error(String),
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Error(_))
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {}, got {})", expected, actual)
+            }
+            Verdict::Missing { actual } => write!(f, "MISSING (got {})", actual),
+            Verdict::Error(message) => write!(f, "FAIL ({})", message),
+        }
+    }
+}
+
+pub fn verify(answers: &Answers, report: &RunReport) -> Verdict {
+    let actual = match &report.outcome {
+        Outcome::Solved(solution) => solution.answer.to_string(),
+        outcome => return Verdict::Error(outcome.to_string()),
+    };
+    match answers.get(report.year, report.day, report.part) {
+        Some(expected) if expected == actual => Verdict::Pass,
+        Some(expected) => Verdict::Fail {
+            expected: expected.to_string(),
+            actual,
+        },
+        None => Verdict::Missing { actual },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runner::Solution;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn solved(day: u32, part: u32, answer: &str) -> RunReport {
+        RunReport {
+            year: 2021,
+            day,
+            part,
+            input_path: PathBuf::from("input.txt"),
+            input_hash: Some(0),
+            outcome: Outcome::Solved(Solution {
+                answer: answer.into(),
+                parse_time: None,
+                solve_time: Duration::ZERO,
+            }),
+        }
+    }
+
+    #[test]
+    fn parse_answers() {
+        let answers = Answers::parse(
+            r##"# checked against the puzzle website
+[2021.day_01]
+part1 = "1624"
+part2 = 1653 # bare integers are accepted too
+
+[2021.day_03]
+part2 = "# not a comment"
+
+[2020.day_01]
+part1 = 1
+
+[day_04]
+part1 = 4512
+"##,
+        )
+        .unwrap();
+        assert_eq!(answers.get(2021, 1, 1), Some("1624"));
+        assert_eq!(answers.get(2021, 1, 2), Some("1653"));
+        assert_eq!(answers.get(2021, 3, 1), None);
+        assert_eq!(answers.get(2021, 3, 2), Some("# not a comment"));
+        assert_eq!(answers.get(2020, 1, 1), Some("1"));
+        assert_eq!(answers.get(2021, 4, 1), Some("4512"));
+    }
+
+    #[test]
+    fn parse_answers_errors() {
+        assert_eq!(
+            Answers::parse("part1 = 3"),
+            Err(AnswersError {
+                line: 1,
+                message: "answer outside of a [YYYY.day_XX] section".to_string()
+            })
+        );
+        assert_eq!(
+            Answers::parse("[2021.day_01]\npart1 = 3\n[day_01]\npart1 = 4"),
+            Err(AnswersError {
+                line: 4,
+                message: "duplicate answer for 2021 day 1 part 1".to_string()
+            })
+        );
+        assert_eq!(
+            Answers::parse("[day_01]\npart1 = \"3"),
+            Err(AnswersError {
+                line: 2,
+                message: "invalid value '\"3'".to_string()
+            })
+        );
+        assert_eq!(
+            Answers::parse("[day_one]"),
+            Err(AnswersError {
+                line: 1,
+                message: "invalid section 'day_one'".to_string()
+            })
+        );
+        assert_eq!(
+            Answers::parse("[twenty.day_01]"),
+            Err(AnswersError {
+                line: 1,
+                message: "invalid section 'twenty.day_01'".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn verify_reports() {
+        let answers = Answers::parse("[2021.day_01]\npart1 = 7\npart2 = 5").unwrap();
+        assert_eq!(verify(&answers, &solved(1, 1, "7")), Verdict::Pass);
+        assert_eq!(
+            verify(&answers, &solved(1, 2, "6")),
+            Verdict::Fail {
+                expected: "5".to_string(),
+                actual: "6".to_string()
+            }
+        );
+        assert_eq!(
+            verify(&answers, &solved(2, 1, "150")),
+            Verdict::Missing {
+                actual: "150".to_string()
+            }
+        );
+    }
+}
@@ -1,4 +1,308 @@
+use crate::json;
+use crate::registry::DEFAULT_YEAR;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Integer(i64),
+    BigUnsigned(u128),
+    Text(String),
+    /// Letters drawn on a grid, one `String` per row.
+    AsciiArt(Vec<String>),
+}
+
+impl Answer {
+    /// Numeric value of the answer, whatever integer variant holds it.
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Answer::Integer(value) => Some(*value as i128),
+            Answer::BigUnsigned(value) => i128::try_from(*value).ok(),
+            Answer::Text(_) | Answer::AsciiArt(_) => None,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Integer(value) => value.to_string(),
+            Answer::BigUnsigned(value) => value.to_string(),
+            Answer::Text(text) => json::string(text),
+            Answer::AsciiArt(lines) => json::array(lines.iter().map(|line| json::string(line))),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(first), Answer::Text(second)) => first == second,
+            (Answer::AsciiArt(first), Answer::AsciiArt(second)) => first == second,
+            (Answer::BigUnsigned(first), Answer::BigUnsigned(second)) => first == second,
+            _ => match (self.as_integer(), other.as_integer()) {
+                (Some(first), Some(second)) => first == second,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::BigUnsigned(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::AsciiArt(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident, $($from:ty),*) => {
+        $(
+            impl From<$from> for Answer {
+                fn from(value: $from) -> Self {
+                    Answer::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(Integer, i8, i16, i32, i64, u8, u16, u32);
+answer_from!(Text, String, &str);
+
+/// Unsigned values that fit in an `i64` are `Integer` too, so the same number is always held
+/// by the same variant whatever type the solver used.
+macro_rules! answer_from_unsigned {
+    ($($from:ty),*) => {
+        $(
+            impl From<$from> for Answer {
+                fn from(value: $from) -> Self {
+                    match i64::try_from(value) {
+                        Ok(value) => Answer::Integer(value),
+                        Err(_) => Answer::BigUnsigned(value as u128),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+answer_from_unsigned!(u64, u128, usize);
+
 pub trait AOCPart {
+    type Parsed;
+
     fn new() -> Self;
-    fn solve(&mut self, input: &str) -> String;
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError>;
+    fn solve(&mut self, parsed: &Self::Parsed) -> Result<Answer, AocError>;
+
+    fn run(&mut self, input: &str) -> Result<Answer, AocError> {
+        let parsed = self.parse(input)?;
+        self.solve(&parsed)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AocError {
+    /// The default year until the registry sets the year of the failing solver.
+    pub year: u32,
+    pub day: u32,
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+/// Where in the puzzle input an error was found, `line` and `column` start at 1.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub source_line: String,
+}
+
+impl Location {
+    /// Location of `text`, which must be a slice of `source_line` (the `line_index`-th input
+    /// line).
+    pub fn new(line_index: usize, source_line: &str, text: &str) -> Self {
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(source_line.as_ptr() as usize)
+            .filter(|offset| *offset <= source_line.len())
+            .unwrap_or(0);
+        Location {
+            line: line_index + 1,
+            column: source_line[..offset].chars().count() + 1,
+            text: text.to_string(),
+            source_line: source_line.to_string(),
+        }
+    }
+}
+
+impl AocError {
+    pub fn new(day: u32, message: impl Into<String>) -> Self {
+        AocError {
+            year: DEFAULT_YEAR,
+            day,
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    /// Error on `text`, which must be a slice of `source_line` (the `line_index`-th input line).
+    pub fn at(
+        day: u32,
+        line_index: usize,
+        source_line: &str,
+        text: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        AocError::located(day, Location::new(line_index, source_line, text), message)
+    }
+
+    /// Error on text located while parsing, for errors found when solving.
+    pub fn located(day: u32, location: Location, message: impl Into<String>) -> Self {
+        AocError {
+            year: DEFAULT_YEAR,
+            day,
+            location: Some(location),
+            message: message.into(),
+        }
+    }
+
+    pub fn in_year(self, year: u32) -> Self {
+        AocError { year, ..self }
+    }
+
+    /// Multi-line report pointing at the offending text, meant for the terminal.
+    pub fn diagnostic(&self) -> String {
+        let location = match &self.location {
+            Some(location) => location,
+            None => return format!("error: {} day {}: {}\n", self.year, self.day, self.message),
+        };
+
+        let gutter = " ".repeat(location.line.to_string().len());
+        format!(
+            "error: {} day {}: {}\n{}--> input line {}, column {}\n{} |\n{} | {}\n{} | {}{}\n",
+            self.year,
+            self.day,
+            self.message,
+            gutter,
+            location.line,
+            location.column,
+            gutter,
+            location.line,
+            location.source_line,
+            gutter,
+            " ".repeat(location.column - 1),
+            "^".repeat(location.text.chars().count().max(1)),
+        )
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(
+                f,
+                "{} day {} line {} column {}: {}",
+                self.year, self.day, location.line, location.column, self.message
+            ),
+            None => write!(f, "{} day {}: {}", self.year, self.day, self.message),
+        }
+    }
+}
+
+impl std::error::Error for AocError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn answer_display() {
+        assert_eq!(Answer::from(-12).to_string(), "-12");
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(Answer::from("ABC").to_string(), "ABC");
+        assert_eq!(
+            Answer::AsciiArt(vec!["#..".to_string(), "###".to_string()]).to_string(),
+            "#..\n###"
+        );
+    }
+
+    #[test]
+    fn unsigned_answers_are_integers_when_they_fit() {
+        assert!(matches!(Answer::from(7usize), Answer::Integer(7)));
+        assert!(matches!(Answer::from(7u64), Answer::Integer(7)));
+        assert!(matches!(
+            Answer::from(i64::MAX as u64),
+            Answer::Integer(i64::MAX)
+        ));
+        assert!(matches!(
+            Answer::from(i64::MAX as u64 + 1),
+            Answer::BigUnsigned(_)
+        ));
+        assert!(matches!(Answer::from(u128::MAX), Answer::BigUnsigned(_)));
+    }
+
+    #[test]
+    fn answer_equality() {
+        assert_eq!(Answer::from(7u32), Answer::from(7usize));
+        assert_eq!(Answer::from(7i64), Answer::BigUnsigned(7));
+        assert_ne!(Answer::from(-7), Answer::BigUnsigned(7));
+        assert_ne!(Answer::from(7), Answer::from("7"));
+        assert_eq!(
+            Answer::BigUnsigned(u128::MAX),
+            Answer::BigUnsigned(u128::MAX)
+        );
+        assert_ne!(Answer::BigUnsigned(u128::MAX), Answer::BigUnsigned(0));
+    }
+
+    #[test]
+    fn answer_json() {
+        assert_eq!(Answer::from(42).to_json(), "42");
+        assert_eq!(Answer::from("a\"b").to_json(), r#""a\"b""#);
+        assert_eq!(
+            Answer::AsciiArt(vec!["#.".to_string(), ".#".to_string()]).to_json(),
+            r##"["#.",".#"]"##
+        );
+    }
+
+    #[test]
+    fn error_location() {
+        let line = "forward five";
+        let error = AocError::at(2, 6, line, &line[8..], "invalid units");
+        assert_eq!(
+            error.location,
+            Some(Location {
+                line: 7,
+                column: 9,
+                text: "five".to_string(),
+                source_line: line.to_string(),
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "2021 day 2 line 7 column 9: invalid units"
+        );
+    }
+
+    #[test]
+    fn error_diagnostic() {
+        let line = "forward five";
+        assert_eq!(
+            AocError::at(2, 11, line, &line[8..], "invalid units").diagnostic(),
+            "error: 2021 day 2: invalid units
+  --> input line 12, column 9
+   |
+12 | forward five
+   |         ^^^^
+"
+        );
+        assert_eq!(
+            AocError::new(3, "empty input").in_year(2022).diagnostic(),
+            "error: 2022 day 3: empty input\n"
+        );
+    }
 }
@@ -0,0 +1,269 @@
+use crate::aoc::AocError;
+use crate::input::InputSource;
+use crate::registry::Solver;
+use crate::runner;
+use crate::table::{self, format_duration};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BenchConfig {
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: 10,
+            iterations: 100,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Stats {
+    pub iterations: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub min: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    /// Panics on an empty sample set.
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "no samples");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let count = sorted.len();
+
+        let mean_nanos = sorted.iter().map(Duration::as_nanos).sum::<u128>() as f64 / count as f64;
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample.as_nanos() as f64 - mean_nanos).powi(2))
+            .sum::<f64>()
+            / count as f64;
+
+        let median = if count.is_multiple_of(2) {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2
+        } else {
+            sorted[count / 2]
+        };
+        // nearest rank
+        let p95 = sorted[(count * 95).div_ceil(100) - 1];
+
+        Stats {
+            iterations: count,
+            mean: Duration::from_nanos(mean_nanos.round() as u64),
+            median,
+            p95,
+            min: sorted[0],
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+}
+
+/// Timings of both phases of a solver, and of each parse and solve pair.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PhaseStats {
+    pub parse: Stats,
+    pub solve: Stats,
+    pub total: Stats,
+}
+
+#[derive(Debug)]
+pub struct BenchReport {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub outcome: Result<PhaseStats, String>,
+}
+
+/// Times parsing and solving apart, `config.warmup` untimed runs first.
+pub fn bench_solver(
+    solver: &Solver,
+    input: &str,
+    config: BenchConfig,
+) -> Result<PhaseStats, AocError> {
+    let mut part = solver.build();
+    for _ in 0..config.warmup {
+        black_box(part.run(black_box(input))?);
+    }
+
+    let mut parse_samples = Vec::with_capacity(config.iterations);
+    let mut solve_samples = Vec::with_capacity(config.iterations);
+    let mut total_samples = Vec::with_capacity(config.iterations);
+    for _ in 0..config.iterations.max(1) {
+        let start = Instant::now();
+        let parsed = black_box(part.parse(black_box(input))?);
+        let parsed_at = Instant::now();
+        black_box(part.solve(parsed.as_ref())?);
+        let solved_at = Instant::now();
+        parse_samples.push(parsed_at - start);
+        solve_samples.push(solved_at - parsed_at);
+        total_samples.push(solved_at - start);
+    }
+    Ok(PhaseStats {
+        parse: Stats::from_samples(&parse_samples),
+        solve: Stats::from_samples(&solve_samples),
+        total: Stats::from_samples(&total_samples),
+    })
+}
+
+/// A solver that panics only fails its own report, like in `runner::run_isolated`.
+pub fn bench_all<'a, I: IntoIterator<Item = &'a Solver>>(
+    solvers: I,
+    config: BenchConfig,
+) -> Vec<BenchReport> {
+    solvers
+        .into_iter()
+        .map(|solver| {
+            let outcome = InputSource::Default
+                .read(solver.year, solver.day)
+                .and_then(|input| bench_isolated(solver, &input, config));
+            BenchReport {
+                year: solver.year,
+                day: solver.day,
+                part: solver.part,
+                outcome,
+            }
+        })
+        .collect()
+}
+
+fn bench_isolated(solver: &Solver, input: &str, config: BenchConfig) -> Result<PhaseStats, String> {
+    runner::catch_panic(|| bench_solver(solver, input, config))
+        .map_err(|message| format!("PANIC: {}", message))?
+        .map_err(|err| err.to_string())
+}
+
+pub fn format_table(reports: &[BenchReport]) -> String {
+    let rows: Vec<Vec<String>> = reports
+        .iter()
+        .map(|report| {
+            let mut row = vec![
+                report.year.to_string(),
+                report.day.to_string(),
+                report.part.to_string(),
+            ];
+            match &report.outcome {
+                Ok(PhaseStats {
+                    parse,
+                    solve,
+                    total,
+                }) => row.extend([
+                    total.iterations.to_string(),
+                    format_duration(parse.median),
+                    format_duration(solve.median),
+                    format_duration(total.mean),
+                    format_duration(total.median),
+                    format_duration(total.p95),
+                    format_duration(total.min),
+                    format_duration(total.std_dev),
+                ]),
+                Err(message) => row.push(message.clone()),
+            }
+            row
+        })
+        .collect();
+    table::render(
+        &[
+            "Year",
+            "Day",
+            "Part",
+            "Runs",
+            "Parse median",
+            "Solve median",
+            "Mean",
+            "Median",
+            "p95",
+            "Min",
+            "Std dev",
+        ],
+        &rows,
+        &[],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::{AOCPart, Answer};
+    use crate::registry;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_micros).collect()
+    }
+
+    #[test]
+    fn stats_odd_samples() {
+        let stats = Stats::from_samples(&micros(&[5, 1, 3, 2, 4]));
+        assert_eq!(stats.iterations, 5);
+        assert_eq!(stats.mean, Duration::from_micros(3));
+        assert_eq!(stats.median, Duration::from_micros(3));
+        assert_eq!(stats.p95, Duration::from_micros(5));
+        assert_eq!(stats.min, Duration::from_micros(1));
+        // sqrt(2) µs
+        assert_eq!(stats.std_dev, Duration::from_nanos(1414));
+    }
+
+    #[test]
+    fn stats_even_samples() {
+        let samples: Vec<u64> = (1..=20).collect();
+        let stats = Stats::from_samples(&micros(&samples));
+        assert_eq!(stats.median, Duration::from_nanos(10_500));
+        assert_eq!(stats.p95, Duration::from_micros(19));
+        assert_eq!(stats.min, Duration::from_micros(1));
+    }
+
+    #[test]
+    fn bench_solver_runs_iterations() {
+        let config = BenchConfig {
+            warmup: 1,
+            iterations: 3,
+        };
+        let stats = bench_solver(registry::find(2021, 1, 1).unwrap(), "1\n2\n3", config).unwrap();
+        assert_eq!(stats.parse.iterations, 3);
+        assert_eq!(stats.solve.iterations, 3);
+        assert_eq!(stats.total.iterations, 3);
+        assert!(stats.total.min <= stats.total.median && stats.total.median <= stats.total.p95);
+        assert!(stats.parse.min + stats.solve.min <= stats.total.min);
+
+        assert!(bench_solver(registry::find(2021, 1, 1).unwrap(), "1\nx", config).is_err());
+    }
+
+    struct Panicker;
+
+    impl AOCPart for Panicker {
+        type Parsed = ();
+
+        fn new() -> Self {
+            Panicker
+        }
+
+        fn parse(&self, _input: &str) -> Result<(), AocError> {
+            Ok(())
+        }
+
+        fn solve(&mut self, _parsed: &()) -> Result<Answer, AocError> {
+            panic!("gave up")
+        }
+    }
+
+    #[test]
+    fn panics_fail_their_own_report() {
+        let solver = Solver::new(2021, 1, 1, || Box::new(Panicker));
+        let config = BenchConfig {
+            warmup: 0,
+            iterations: 1,
+        };
+        assert_eq!(
+            bench_isolated(&solver, "", config),
+            Err("PANIC: gave up".to_string())
+        );
+        assert!(bench_isolated(registry::find(2021, 1, 1).unwrap(), "1\n2", config).is_ok());
+    }
+}
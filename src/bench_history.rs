@@ -0,0 +1,409 @@
+use crate::bench::{BenchReport, Stats};
+use crate::table::{self, format_duration};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_PATH: &str = "bench_history.csv";
+
+const HEADER: &str =
+    "timestamp,commit,year,day,part,iterations,mean_ns,median_ns,p95_ns,min_ns,std_dev_ns";
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub commit: String,
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    /// Parse and solve together.
+    pub stats: Stats,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl HistoryEntry {
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            self.timestamp,
+            self.commit,
+            self.year,
+            self.day,
+            self.part,
+            self.stats.iterations,
+            self.stats.mean.as_nanos(),
+            self.stats.median.as_nanos(),
+            self.stats.p95.as_nanos(),
+            self.stats.min.as_nanos(),
+            self.stats.std_dev.as_nanos(),
+        )
+    }
+
+    fn from_csv(line: &str) -> Option<HistoryEntry> {
+        let fields: Vec<&str> = line.split(',').collect();
+        let [timestamp, commit, year, day, part, iterations, mean, median, p95, min, std_dev] =
+            fields[..]
+        else {
+            return None;
+        };
+        let nanos = |field: &str| field.parse().ok().map(Duration::from_nanos);
+        Some(HistoryEntry {
+            timestamp: timestamp.parse().ok()?,
+            commit: commit.to_string(),
+            year: year.parse().ok()?,
+            day: day.parse().ok()?,
+            part: part.parse().ok()?,
+            stats: Stats {
+                iterations: iterations.parse().ok()?,
+                mean: nanos(mean)?,
+                median: nanos(median)?,
+                p95: nanos(p95)?,
+                min: nanos(min)?,
+                std_dev: nanos(std_dev)?,
+            },
+        })
+    }
+}
+
+impl History {
+    /// A missing file is an empty history.
+    pub fn load(path: &Path) -> Result<History, String> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => History::parse(&content)
+                .map_err(|line| format!("{}: invalid entry on line {}", path.display(), line)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(History::default()),
+            Err(err) => Err(format!("cannot read '{}': {}", path.display(), err)),
+        }
+    }
+
+    /// On failure, returns the number of the offending line.
+    pub fn parse(content: &str) -> Result<History, usize> {
+        let entries = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && *line != HEADER)
+            .map(|(index, line)| HistoryEntry::from_csv(line).ok_or(index + 1))
+            .collect::<Result<_, _>>()?;
+        Ok(History { entries })
+    }
+
+    pub fn append(path: &Path, entries: &[HistoryEntry]) -> Result<(), String> {
+        let error = |err: std::io::Error| format!("cannot write '{}': {}", path.display(), err);
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(error)?;
+        if is_new {
+            writeln!(file, "{}", HEADER).map_err(error)?;
+        }
+        for entry in entries {
+            writeln!(file, "{}", entry.to_csv()).map_err(error)?;
+        }
+        Ok(())
+    }
+
+    /// Most recent entry for the solver, only looking at `commit` (a prefix) if given.
+    pub fn baseline(
+        &self,
+        year: u32,
+        day: u32,
+        part: u32,
+        commit: Option<&str>,
+    ) -> Option<&HistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| (entry.year, entry.day, entry.part) == (year, day, part))
+            .filter(|entry| commit.is_none_or(|commit| entry.commit.starts_with(commit)))
+            .max_by_key(|entry| entry.timestamp)
+    }
+}
+
+pub fn entries_from_reports(
+    reports: &[BenchReport],
+    timestamp: u64,
+    commit: &str,
+) -> Vec<HistoryEntry> {
+    reports
+        .iter()
+        .filter_map(|report| {
+            report.outcome.as_ref().ok().map(|stats| HistoryEntry {
+                timestamp,
+                commit: commit.to_string(),
+                year: report.year,
+                day: report.day,
+                part: report.part,
+                stats: stats.total,
+            })
+        })
+        .collect()
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Short hash of the checked out commit, `unknown` outside of a git work tree.
+pub fn current_commit() -> String {
+    std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub current: Stats,
+    pub baseline: Option<HistoryEntry>,
+    pub regressed: bool,
+}
+
+impl Comparison {
+    /// Relative change of the median, in percent.
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline.as_ref()?.stats.median.as_nanos() as f64;
+        if baseline == 0.0 {
+            return None;
+        }
+        Some((self.current.median.as_nanos() as f64 - baseline) / baseline * 100.0)
+    }
+}
+
+pub fn compare(
+    reports: &[BenchReport],
+    history: &History,
+    baseline_commit: Option<&str>,
+    threshold_percent: u32,
+) -> Vec<Comparison> {
+    reports
+        .iter()
+        .filter_map(|report| {
+            let current = report.outcome.as_ref().ok()?.total;
+            let baseline = history
+                .baseline(report.year, report.day, report.part, baseline_commit)
+                .cloned();
+            let mut comparison = Comparison {
+                year: report.year,
+                day: report.day,
+                part: report.part,
+                current,
+                baseline,
+                regressed: false,
+            };
+            comparison.regressed = comparison
+                .change()
+                .is_some_and(|change| change > threshold_percent as f64);
+            Some(comparison)
+        })
+        .collect()
+}
+
+pub fn format_comparison(comparisons: &[Comparison]) -> String {
+    let rows: Vec<Vec<String>> = comparisons
+        .iter()
+        .map(|comparison| {
+            let (baseline, change, status) = match (&comparison.baseline, comparison.change()) {
+                (Some(baseline), Some(change)) => (
+                    format!(
+                        "{} ({})",
+                        format_duration(baseline.stats.median),
+                        baseline.commit
+                    ),
+                    format!("{:+.1}%", change),
+                    if comparison.regressed {
+                        "REGRESSED"
+                    } else {
+                        "ok"
+                    },
+                ),
+                _ => ("-".to_string(), "-".to_string(), "new"),
+            };
+            vec![
+                comparison.year.to_string(),
+                comparison.day.to_string(),
+                comparison.part.to_string(),
+                format_duration(comparison.current.median),
+                baseline,
+                change,
+                status.to_string(),
+            ]
+        })
+        .collect();
+    table::render(
+        &[
+            "Year", "Day", "Part", "Median", "Baseline", "Change", "Status",
+        ],
+        &rows,
+        &[],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bench::PhaseStats;
+
+    fn stats(median_micros: u64) -> Stats {
+        let median = Duration::from_micros(median_micros);
+        Stats {
+            iterations: 10,
+            mean: median,
+            median,
+            p95: median,
+            min: median,
+            std_dev: Duration::ZERO,
+        }
+    }
+
+    fn phases(total_micros: u64) -> PhaseStats {
+        PhaseStats {
+            parse: stats(0),
+            solve: stats(total_micros),
+            total: stats(total_micros),
+        }
+    }
+
+    fn entry(timestamp: u64, commit: &str, day: u32, median_micros: u64) -> HistoryEntry {
+        HistoryEntry {
+            timestamp,
+            commit: commit.to_string(),
+            year: 2021,
+            day,
+            part: 1,
+            stats: stats(median_micros),
+        }
+    }
+
+    #[test]
+    fn entry_csv_roundtrip() {
+        let entry = entry(1_700_000_000, "abc1234", 4, 250);
+        assert_eq!(
+            entry.to_csv(),
+            "1700000000,abc1234,2021,4,1,10,250000,250000,250000,250000,0"
+        );
+        assert_eq!(HistoryEntry::from_csv(&entry.to_csv()), Some(entry));
+        assert_eq!(
+            HistoryEntry::from_csv("1700000000,abc1234,4,1,10,250000,250000,250000,250000,0"),
+            None
+        );
+        assert_eq!(HistoryEntry::from_csv("1,abc,4,1"), None);
+    }
+
+    #[test]
+    fn parse_history() {
+        let history = History::parse(&format!(
+            "{}\n1,aaa,2021,1,1,10,1,1,1,1,0\n\n2,bbb,2021,1,1,10,2,2,2,2,0\n",
+            HEADER
+        ))
+        .unwrap();
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(History::parse("1,aaa,2021,1,1,10,1,1,1,1,0\nnope"), Err(2));
+    }
+
+    #[test]
+    fn baseline_is_latest_entry() {
+        let history = History {
+            entries: vec![
+                entry(3, "ccc", 1, 30),
+                entry(1, "aaa", 1, 10),
+                entry(2, "bbb", 1, 20),
+                entry(4, "ddd", 2, 40),
+            ],
+        };
+        assert_eq!(history.baseline(2021, 1, 1, None).unwrap().commit, "ccc");
+        assert_eq!(
+            history.baseline(2021, 1, 1, Some("bb")).unwrap().commit,
+            "bbb"
+        );
+        assert_eq!(history.baseline(2021, 1, 2, None), None);
+        assert_eq!(history.baseline(2021, 3, 1, None), None);
+        assert_eq!(history.baseline(2020, 1, 1, None), None);
+    }
+
+    #[test]
+    fn compare_flags_regressions() {
+        let history = History {
+            entries: vec![entry(1, "aaa", 1, 100), entry(1, "aaa", 2, 100)],
+        };
+        let reports = vec![
+            BenchReport {
+                year: 2021,
+                day: 1,
+                part: 1,
+                outcome: Ok(phases(105)),
+            },
+            BenchReport {
+                year: 2021,
+                day: 2,
+                part: 1,
+                outcome: Ok(phases(150)),
+            },
+            BenchReport {
+                year: 2021,
+                day: 3,
+                part: 1,
+                outcome: Ok(phases(1)),
+            },
+            BenchReport {
+                year: 2021,
+                day: 4,
+                part: 1,
+                outcome: Err("no input".to_string()),
+            },
+        ];
+
+        let comparisons = compare(&reports, &history, None, 10);
+        let regressed: Vec<_> = comparisons
+            .iter()
+            .map(|comparison| (comparison.day, comparison.regressed))
+            .collect();
+        assert_eq!(regressed, vec![(1, false), (2, true), (3, false)]);
+        assert_eq!(comparisons[1].change(), Some(50.0));
+        assert_eq!(comparisons[2].change(), None);
+
+        assert_eq!(
+            format_comparison(&comparisons),
+            "Year | Day | Part | Median   | Baseline       | Change | Status
+-----+-----+------+----------+----------------+--------+----------
+2021 | 1   | 1    | 105.00µs | 100.00µs (aaa) | +5.0%  | ok
+2021 | 2   | 1    | 150.00µs | 100.00µs (aaa) | +50.0% | REGRESSED
+2021 | 3   | 1    | 1.00µs   | -              | -      | new
+"
+        );
+    }
+
+    #[test]
+    fn append_and_load() {
+        let path =
+            std::env::temp_dir().join(format!("aoc_bench_history_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(History::load(&path), Ok(History::default()));
+        History::append(&path, &[entry(1, "aaa", 1, 10)]).unwrap();
+        History::append(&path, &[entry(2, "bbb", 1, 20)]).unwrap();
+        let history = History::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            history.entries,
+            vec![entry(1, "aaa", 1, 10), entry(2, "bbb", 1, 20)]
+        );
+    }
+}
@@ -0,0 +1,948 @@
+use crate::bench::BenchConfig;
+use crate::differential::DEFAULT_CASES;
+use crate::fuzz::DEFAULT_ITERATIONS;
+use crate::input::InputSource;
+use crate::registry::DEFAULT_YEAR;
+use crate::runner::{Format, DEFAULT_TIMEOUT};
+use crate::watch::WatchConfig;
+use std::fmt;
+use std::time::Duration;
+
+pub const USAGE: &str = "usage: aoc_2021 run [--year <year>] --day <day> --part <part> [<input> | - | --example <n>]
+                    [--format <format>] [--timeout <seconds>]
+       aoc_2021 run --all [--year <year>] [--format <format>] [--timeout <seconds>]
+       aoc_2021 verify [--answers <answers.toml>]
+       aoc_2021 bench [--year <year>] [--day <day>] [--part <part>] [--iterations <n>] [--warmup <n>]
+                      [--compare [--baseline <commit>] [--threshold <percent>]]
+                      [--history <file.csv>] [--save | --no-save]
+       aoc_2021 watch [--year <year>] --day <day> [--interval <ms>]
+       aoc_2021 show [--year <year>] --day <day> [--part <part>] [--width <columns>]
+       aoc_2021 fetch [--year <year>] --day <day> [--base-url <url>]
+       aoc_2021 submit [--year <year>] --day <day> --part <part> [--base-url <url>]
+       aoc_2021 new [--year <year>] --day <day>
+       aoc_2021 repl
+       aoc_2021 gen [--year <year>] --day <day> [--size <records>] [--seed <n>]
+       aoc_2021 diff [--year <year>] [--day <day>] [--part <part>] [--cases <n>] [--size <records>]
+                     [--seed <n>]
+       aoc_2021 fuzz [--year <year>] [--day <day>] [--iterations <n>] [--seed <n>]
+
+<year> defaults to 2021.";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(RunArgs),
+    RunAll(RunAllArgs),
+    Verify(VerifyArgs),
+    Bench(BenchArgs),
+    Watch(WatchConfig),
+    Show(ShowArgs),
+    Fetch(FetchArgs),
+    Submit(SubmitArgs),
+    New(NewArgs),
+    Repl,
+    Gen(GenArgs),
+    Diff(DiffArgs),
+    Fuzz(FuzzArgs),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunArgs {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub input: InputSource,
+    pub format: Format,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunAllArgs {
+    /// Every year when `None`.
+    pub year: Option<u32>,
+    pub format: Format,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifyArgs {
+    pub answers: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchArgs {
+    pub year: Option<u32>,
+    pub day: Option<u32>,
+    pub part: Option<u32>,
+    pub config: BenchConfig,
+    pub history: String,
+    /// Defaults to saving unless `compare` is set, so a compared run never becomes the next
+    /// baseline by accident.
+    pub save: bool,
+    pub compare: bool,
+    pub baseline: Option<String>,
+    /// Allowed slowdown of the median before flagging a regression.
+    pub threshold_percent: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ShowArgs {
+    pub year: u32,
+    pub day: u32,
+    /// Both parts when `None`.
+    pub part: Option<u32>,
+    /// Terminal width when `None`.
+    pub width: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FetchArgs {
+    pub year: u32,
+    pub day: u32,
+    pub base_url: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SubmitArgs {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub base_url: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NewArgs {
+    pub year: u32,
+    pub day: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GenArgs {
+    pub year: u32,
+    pub day: u32,
+    /// The size of the real input when `None`.
+    pub size: Option<usize>,
+    pub seed: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DiffArgs {
+    /// Every year when `None`.
+    pub year: Option<u32>,
+    /// Every day when `None`.
+    pub day: Option<u32>,
+    /// Both parts when `None`.
+    pub part: Option<u32>,
+    pub cases: u64,
+    /// Largest generated input, the size of the real input when `None`.
+    pub size: Option<usize>,
+    pub seed: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FuzzArgs {
+    /// Every year when `None`.
+    pub year: Option<u32>,
+    /// Every day when `None`.
+    pub day: Option<u32>,
+    pub iterations: u64,
+    pub seed: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliError {
+    MissingCommand,
+    UnknownCommand(String),
+    MissingValue(String),
+    InvalidValue { flag: String, value: String },
+    MissingFlag(String),
+    UnexpectedArgument(String),
+    ConflictingArguments(String, String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::MissingCommand => write!(f, "missing command"),
+            CliError::UnknownCommand(command) => write!(f, "unknown command '{}'", command),
+            CliError::MissingValue(flag) => write!(f, "missing value for '{}'", flag),
+            CliError::InvalidValue { flag, value } => {
+                write!(f, "invalid value '{}' for '{}'", value, flag)
+            }
+            CliError::MissingFlag(flag) => write!(f, "missing required '{}'", flag),
+            CliError::UnexpectedArgument(arg) => write!(f, "unexpected argument '{}'", arg),
+            CliError::ConflictingArguments(first, second) => {
+                write!(f, "'{}' cannot be used with '{}'", first, second)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+pub fn parse_args(args: &[String]) -> Result<Command, CliError> {
+    let (command, args) = args.split_first().ok_or(CliError::MissingCommand)?;
+    match command.as_str() {
+        "run" => parse_run_args(args),
+        "verify" => parse_verify_args(args).map(Command::Verify),
+        "bench" => parse_bench_args(args).map(Command::Bench),
+        "watch" => parse_watch_args(args).map(Command::Watch),
+        "show" => parse_show_args(args).map(Command::Show),
+        "fetch" => parse_fetch_args(args).map(Command::Fetch),
+        "submit" => parse_submit_args(args).map(Command::Submit),
+        "new" => parse_new_args(args).map(Command::New),
+        "repl" => match args.first() {
+            Some(arg) => Err(CliError::UnexpectedArgument(arg.clone())),
+            None => Ok(Command::Repl),
+        },
+        "gen" => parse_gen_args(args).map(Command::Gen),
+        "diff" => parse_diff_args(args).map(Command::Diff),
+        "fuzz" => parse_fuzz_args(args).map(Command::Fuzz),
+        _ => Err(CliError::UnknownCommand(command.clone())),
+    }
+}
+
+fn parse_run_args(args: &[String]) -> Result<Command, CliError> {
+    let mut all = false;
+    let mut year = None;
+    let mut day = None;
+    let mut part = None;
+    let mut input: Option<String> = None;
+    let mut example: Option<usize> = None;
+    let mut format = Format::Table;
+    let mut timeout = DEFAULT_TIMEOUT;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--year" => year = Some(parse_flag_value(arg, args.next())?),
+            "--day" => day = Some(parse_flag_value(arg, args.next())?),
+            "--part" => part = Some(parse_flag_value(arg, args.next())?),
+            "--example" => example = Some(parse_flag_value(arg, args.next())?),
+            "--format" => format = parse_flag_value(arg, args.next())?,
+            "--timeout" => timeout = Duration::from_secs(parse_flag_value(arg, args.next())?),
+            "-" if input.is_none() => input = Some(arg.clone()),
+            _ if arg.starts_with("--") || input.is_some() => {
+                return Err(CliError::UnexpectedArgument(arg.clone()))
+            }
+            _ => input = Some(arg.clone()),
+        }
+    }
+
+    if all {
+        let conflict = match (day, part, example, input) {
+            (Some(_), _, _, _) => Some("--day".to_string()),
+            (_, Some(_), _, _) => Some("--part".to_string()),
+            (_, _, Some(_), _) => Some("--example".to_string()),
+            (_, _, _, Some(input)) => Some(input),
+            _ => None,
+        };
+        return match conflict {
+            Some(conflict) => Err(CliError::ConflictingArguments(
+                "--all".to_string(),
+                conflict,
+            )),
+            None => Ok(Command::RunAll(RunAllArgs {
+                year,
+                format,
+                timeout,
+            })),
+        };
+    }
+
+    let input = match (input, example) {
+        (Some(input), Some(_)) => {
+            return Err(CliError::ConflictingArguments(
+                "--example".to_string(),
+                input,
+            ))
+        }
+        (Some(input), None) => InputSource::from_arg(&input),
+        (None, Some(example)) => InputSource::Example(example),
+        (None, None) => InputSource::Default,
+    };
+
+    Ok(Command::Run(RunArgs {
+        year: year.unwrap_or(DEFAULT_YEAR),
+        day: day.ok_or_else(|| CliError::MissingFlag("--day".to_string()))?,
+        part: part.ok_or_else(|| CliError::MissingFlag("--part".to_string()))?,
+        input,
+        format,
+        timeout,
+    }))
+}
+
+fn parse_verify_args(args: &[String]) -> Result<VerifyArgs, CliError> {
+    let mut answers = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => answers = Some(parse_flag_value(arg, args.next())?),
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    Ok(VerifyArgs {
+        answers: answers.unwrap_or_else(|| crate::answers::DEFAULT_PATH.to_string()),
+    })
+}
+
+fn parse_bench_args(args: &[String]) -> Result<BenchArgs, CliError> {
+    let mut bench = BenchArgs {
+        year: None,
+        day: None,
+        part: None,
+        config: BenchConfig::default(),
+        history: crate::bench_history::DEFAULT_PATH.to_string(),
+        save: true,
+        compare: false,
+        baseline: None,
+        threshold_percent: 10,
+    };
+
+    let mut save = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => bench.year = Some(parse_flag_value(arg, args.next())?),
+            "--day" => bench.day = Some(parse_flag_value(arg, args.next())?),
+            "--part" => bench.part = Some(parse_flag_value(arg, args.next())?),
+            "--iterations" => bench.config.iterations = parse_flag_value(arg, args.next())?,
+            "--warmup" => bench.config.warmup = parse_flag_value(arg, args.next())?,
+            "--history" => bench.history = parse_flag_value(arg, args.next())?,
+            "--save" => save = Some(true),
+            "--no-save" => save = Some(false),
+            "--compare" => bench.compare = true,
+            "--baseline" => bench.baseline = Some(parse_flag_value(arg, args.next())?),
+            "--threshold" => bench.threshold_percent = parse_flag_value(arg, args.next())?,
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    bench.save = save.unwrap_or(!bench.compare);
+    if bench.baseline.is_some() && !bench.compare {
+        return Err(CliError::MissingFlag("--compare".to_string()));
+    }
+    if bench.config.iterations == 0 {
+        return Err(CliError::InvalidValue {
+            flag: "--iterations".to_string(),
+            value: "0".to_string(),
+        });
+    }
+    Ok(bench)
+}
+
+fn parse_watch_args(args: &[String]) -> Result<WatchConfig, CliError> {
+    let mut year = DEFAULT_YEAR;
+    let mut day = None;
+    let mut interval_ms = 500;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => year = parse_flag_value(arg, args.next())?,
+            "--day" => day = Some(parse_flag_value(arg, args.next())?),
+            "--interval" => interval_ms = parse_flag_value(arg, args.next())?,
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    Ok(WatchConfig {
+        year,
+        day: day.ok_or_else(|| CliError::MissingFlag("--day".to_string()))?,
+        interval: Duration::from_millis(interval_ms),
+    })
+}
+
+fn parse_show_args(args: &[String]) -> Result<ShowArgs, CliError> {
+    let mut year = DEFAULT_YEAR;
+    let mut day = None;
+    let mut part: Option<u32> = None;
+    let mut width = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => year = parse_flag_value(arg, args.next())?,
+            "--day" => day = Some(parse_flag_value(arg, args.next())?),
+            "--part" => part = Some(parse_flag_value(arg, args.next())?),
+            "--width" => width = Some(parse_flag_value(arg, args.next())?),
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    if let Some(part) = part.filter(|part| !(1..=2).contains(part)) {
+        return Err(CliError::InvalidValue {
+            flag: "--part".to_string(),
+            value: part.to_string(),
+        });
+    }
+    Ok(ShowArgs {
+        year,
+        day: day.ok_or_else(|| CliError::MissingFlag("--day".to_string()))?,
+        part,
+        width,
+    })
+}
+
+fn parse_fetch_args(args: &[String]) -> Result<FetchArgs, CliError> {
+    let mut year = DEFAULT_YEAR;
+    let mut day = None;
+    let mut base_url = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => year = parse_flag_value(arg, args.next())?,
+            "--day" => day = Some(parse_flag_value(arg, args.next())?),
+            "--base-url" => base_url = Some(parse_flag_value(arg, args.next())?),
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    Ok(FetchArgs {
+        year,
+        day: day.ok_or_else(|| CliError::MissingFlag("--day".to_string()))?,
+        base_url: base_url.unwrap_or_else(|| crate::fetch::DEFAULT_BASE_URL.to_string()),
+    })
+}
+
+fn parse_submit_args(args: &[String]) -> Result<SubmitArgs, CliError> {
+    let mut year = DEFAULT_YEAR;
+    let mut day = None;
+    let mut part = None;
+    let mut base_url = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => year = parse_flag_value(arg, args.next())?,
+            "--day" => day = Some(parse_flag_value(arg, args.next())?),
+            "--part" => part = Some(parse_flag_value(arg, args.next())?),
+            "--base-url" => base_url = Some(parse_flag_value(arg, args.next())?),
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    Ok(SubmitArgs {
+        year,
+        day: day.ok_or_else(|| CliError::MissingFlag("--day".to_string()))?,
+        part: part.ok_or_else(|| CliError::MissingFlag("--part".to_string()))?,
+        base_url: base_url.unwrap_or_else(|| crate::fetch::DEFAULT_BASE_URL.to_string()),
+    })
+}
+
+fn parse_new_args(args: &[String]) -> Result<NewArgs, CliError> {
+    let mut year = DEFAULT_YEAR;
+    let mut day = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => year = parse_flag_value(arg, args.next())?,
+            "--day" => day = Some(parse_flag_value(arg, args.next())?),
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    Ok(NewArgs {
+        year,
+        day: day.ok_or_else(|| CliError::MissingFlag("--day".to_string()))?,
+    })
+}
+
+fn parse_gen_args(args: &[String]) -> Result<GenArgs, CliError> {
+    let mut year = DEFAULT_YEAR;
+    let mut day = None;
+    let mut size = None;
+    let mut seed = 0;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => year = parse_flag_value(arg, args.next())?,
+            "--day" => day = Some(parse_flag_value(arg, args.next())?),
+            "--size" => size = Some(parse_flag_value(arg, args.next())?),
+            "--seed" => seed = parse_flag_value(arg, args.next())?,
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    Ok(GenArgs {
+        year,
+        day: day.ok_or_else(|| CliError::MissingFlag("--day".to_string()))?,
+        size,
+        seed,
+    })
+}
+
+fn parse_diff_args(args: &[String]) -> Result<DiffArgs, CliError> {
+    let mut diff = DiffArgs {
+        year: None,
+        day: None,
+        part: None,
+        cases: DEFAULT_CASES,
+        size: None,
+        seed: 0,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => diff.year = Some(parse_flag_value(arg, args.next())?),
+            "--day" => diff.day = Some(parse_flag_value(arg, args.next())?),
+            "--part" => diff.part = Some(parse_flag_value(arg, args.next())?),
+            "--cases" => diff.cases = parse_flag_value(arg, args.next())?,
+            "--size" => diff.size = Some(parse_flag_value(arg, args.next())?),
+            "--seed" => diff.seed = parse_flag_value(arg, args.next())?,
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    Ok(diff)
+}
+
+fn parse_fuzz_args(args: &[String]) -> Result<FuzzArgs, CliError> {
+    let mut fuzz = FuzzArgs {
+        year: None,
+        day: None,
+        iterations: DEFAULT_ITERATIONS,
+        seed: 0,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => fuzz.year = Some(parse_flag_value(arg, args.next())?),
+            "--day" => fuzz.day = Some(parse_flag_value(arg, args.next())?),
+            "--iterations" => fuzz.iterations = parse_flag_value(arg, args.next())?,
+            "--seed" => fuzz.seed = parse_flag_value(arg, args.next())?,
+            _ => return Err(CliError::UnexpectedArgument(arg.clone())),
+        }
+    }
+
+    Ok(fuzz)
+}
+
+fn parse_flag_value<T: std::str::FromStr>(
+    flag: &str,
+    value: Option<&String>,
+) -> Result<T, CliError> {
+    let value = value.ok_or_else(|| CliError::MissingValue(flag.to_string()))?;
+    value.parse().map_err(|_| CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.clone(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_run() {
+        assert_eq!(
+            parse_args(&args("run --day 3 --part 1 inputs/2021/day_03.txt")),
+            Ok(Command::Run(RunArgs {
+                year: 2021,
+                day: 3,
+                part: 1,
+                input: InputSource::File("inputs/2021/day_03.txt".into()),
+                format: Format::Table,
+                timeout: DEFAULT_TIMEOUT,
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(
+                "run in.txt --part 2 --day 4 --format csv --year 2020 --timeout 60"
+            )),
+            Ok(Command::Run(RunArgs {
+                year: 2020,
+                day: 4,
+                part: 2,
+                input: InputSource::File("in.txt".into()),
+                format: Format::Csv,
+                timeout: Duration::from_secs(60),
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_run_input_sources() {
+        let input = |line| match parse_args(&args(line)) {
+            Ok(Command::Run(run)) => Ok(run.input),
+            Ok(command) => panic!("unexpected command {:?}", command),
+            Err(err) => Err(err),
+        };
+        assert_eq!(input("run --day 1 --part 1"), Ok(InputSource::Default));
+        assert_eq!(input("run --day 1 --part 1 -"), Ok(InputSource::Stdin));
+        assert_eq!(
+            input("run --day 4 --example 1 --part 1"),
+            Ok(InputSource::Example(1))
+        );
+        assert_eq!(
+            input("run --day 4 --part 1 --example 1 input.txt"),
+            Err(CliError::ConflictingArguments(
+                "--example".to_string(),
+                "input.txt".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_run_all() {
+        assert_eq!(
+            parse_args(&args("run --all")),
+            Ok(Command::RunAll(RunAllArgs {
+                year: None,
+                format: Format::Table,
+                timeout: DEFAULT_TIMEOUT
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("run --format json --all --year 2021 --timeout 1")),
+            Ok(Command::RunAll(RunAllArgs {
+                year: Some(2021),
+                format: Format::Json,
+                timeout: Duration::from_secs(1)
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("run --all --day 3")),
+            Err(CliError::ConflictingArguments(
+                "--all".to_string(),
+                "--day".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_args(&args("run --all inputs/day_03.txt")),
+            Err(CliError::ConflictingArguments(
+                "--all".to_string(),
+                "inputs/day_03.txt".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_verify() {
+        assert_eq!(
+            parse_args(&args("verify")),
+            Ok(Command::Verify(VerifyArgs {
+                answers: "answers.toml".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("verify --answers other.toml")),
+            Ok(Command::Verify(VerifyArgs {
+                answers: "other.toml".to_string()
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_bench() {
+        let default = || BenchArgs {
+            year: None,
+            day: None,
+            part: None,
+            config: BenchConfig::default(),
+            history: "bench_history.csv".to_string(),
+            save: true,
+            compare: false,
+            baseline: None,
+            threshold_percent: 10,
+        };
+        assert_eq!(parse_args(&args("bench")), Ok(Command::Bench(default())));
+        assert_eq!(
+            parse_args(&args(
+                "bench --year 2021 --day 3 --iterations 500 --warmup 0 --no-save"
+            )),
+            Ok(Command::Bench(BenchArgs {
+                year: Some(2021),
+                day: Some(3),
+                config: BenchConfig {
+                    warmup: 0,
+                    iterations: 500,
+                },
+                save: false,
+                ..default()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("bench --compare --baseline abc123 --threshold 25")),
+            Ok(Command::Bench(BenchArgs {
+                compare: true,
+                baseline: Some("abc123".to_string()),
+                threshold_percent: 25,
+                save: false,
+                ..default()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("bench --compare --save")),
+            Ok(Command::Bench(BenchArgs {
+                compare: true,
+                ..default()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("bench --baseline abc123")),
+            Err(CliError::MissingFlag("--compare".to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("bench --iterations 0")),
+            Err(CliError::InvalidValue {
+                flag: "--iterations".to_string(),
+                value: "0".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_watch() {
+        assert_eq!(
+            parse_args(&args("watch --day 4")),
+            Ok(Command::Watch(WatchConfig {
+                year: 2021,
+                day: 4,
+                interval: Duration::from_millis(500)
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("watch --interval 100 --day 2 --year 2020")),
+            Ok(Command::Watch(WatchConfig {
+                year: 2020,
+                day: 2,
+                interval: Duration::from_millis(100)
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("watch")),
+            Err(CliError::MissingFlag("--day".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_show() {
+        assert_eq!(
+            parse_args(&args("show --day 4")),
+            Ok(Command::Show(ShowArgs {
+                year: 2021,
+                day: 4,
+                part: None,
+                width: None
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("show --day 3 --part 2 --width 60")),
+            Ok(Command::Show(ShowArgs {
+                year: 2021,
+                day: 3,
+                part: Some(2),
+                width: Some(60)
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("show --day 3 --part 3")),
+            Err(CliError::InvalidValue {
+                flag: "--part".to_string(),
+                value: "3".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_fetch() {
+        assert_eq!(
+            parse_args(&args("fetch --day 5")),
+            Ok(Command::Fetch(FetchArgs {
+                year: 2021,
+                day: 5,
+                base_url: "https://adventofcode.com".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(
+                "fetch --base-url http://localhost:8000 --day 5 --year 2015"
+            )),
+            Ok(Command::Fetch(FetchArgs {
+                year: 2015,
+                day: 5,
+                base_url: "http://localhost:8000".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("fetch --base-url http://localhost:8000")),
+            Err(CliError::MissingFlag("--day".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_submit() {
+        assert_eq!(
+            parse_args(&args("submit --day 4 --part 2")),
+            Ok(Command::Submit(SubmitArgs {
+                year: 2021,
+                day: 4,
+                part: 2,
+                base_url: "https://adventofcode.com".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("submit --day 4 --base-url http://localhost:8000")),
+            Err(CliError::MissingFlag("--part".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_new() {
+        assert_eq!(
+            parse_args(&args("new --day 5")),
+            Ok(Command::New(NewArgs { year: 2021, day: 5 }))
+        );
+        assert_eq!(
+            parse_args(&args("new --year 2022 --day 1")),
+            Ok(Command::New(NewArgs { year: 2022, day: 1 }))
+        );
+        assert_eq!(
+            parse_args(&args("new --day 5 src/day_05.rs")),
+            Err(CliError::UnexpectedArgument("src/day_05.rs".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_repl() {
+        assert_eq!(parse_args(&args("repl")), Ok(Command::Repl));
+        assert_eq!(
+            parse_args(&args("repl --day 1")),
+            Err(CliError::UnexpectedArgument("--day".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_gen() {
+        assert_eq!(
+            parse_args(&args("gen --day 3")),
+            Ok(Command::Gen(GenArgs {
+                year: 2021,
+                day: 3,
+                size: None,
+                seed: 0
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("gen --day 4 --size 1000 --seed 42")),
+            Ok(Command::Gen(GenArgs {
+                year: 2021,
+                day: 4,
+                size: Some(1000),
+                seed: 42
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("gen --size 10")),
+            Err(CliError::MissingFlag("--day".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_diff() {
+        assert_eq!(
+            parse_args(&args("diff")),
+            Ok(Command::Diff(DiffArgs {
+                year: None,
+                day: None,
+                part: None,
+                cases: DEFAULT_CASES,
+                size: None,
+                seed: 0
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(
+                "diff --day 3 --part 1 --cases 500 --size 40 --seed 9"
+            )),
+            Ok(Command::Diff(DiffArgs {
+                year: None,
+                day: Some(3),
+                part: Some(1),
+                cases: 500,
+                size: Some(40),
+                seed: 9
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_fuzz() {
+        assert_eq!(
+            parse_args(&args("fuzz")),
+            Ok(Command::Fuzz(FuzzArgs {
+                year: None,
+                day: None,
+                iterations: DEFAULT_ITERATIONS,
+                seed: 0
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("fuzz --day 4 --iterations 100 --seed 7")),
+            Ok(Command::Fuzz(FuzzArgs {
+                year: None,
+                day: Some(4),
+                iterations: 100,
+                seed: 7
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("fuzz --seed x")),
+            Err(CliError::InvalidValue {
+                flag: "--seed".to_string(),
+                value: "x".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse_args(&[]), Err(CliError::MissingCommand));
+        assert_eq!(
+            parse_args(&args("walk")),
+            Err(CliError::UnknownCommand("walk".to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("run --day")),
+            Err(CliError::MissingValue("--day".to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("run --day three")),
+            Err(CliError::InvalidValue {
+                flag: "--day".to_string(),
+                value: "three".to_string()
+            })
+        );
+        assert_eq!(
+            parse_args(&args("run --all --format xml")),
+            Err(CliError::InvalidValue {
+                flag: "--format".to_string(),
+                value: "xml".to_string()
+            })
+        );
+        assert_eq!(
+            parse_args(&args("run --day 3 input.txt")),
+            Err(CliError::MissingFlag("--part".to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("run --day 3 --part 1 --input")),
+            Err(CliError::UnexpectedArgument("--input".to_string()))
+        );
+        assert_eq!(
+            parse_args(&args("run --day 3 --part 1 a.txt b.txt")),
+            Err(CliError::UnexpectedArgument("b.txt".to_string()))
+        );
+    }
+}
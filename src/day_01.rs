@@ -1,56 +1,143 @@
-use crate::aoc::AOCPart;
+use crate::aoc::{AOCPart, Answer, AocError};
+use crate::repl::{Helper, IntoValue};
+
+const DAY: u32 = 1;
 
 pub struct Part1 {}
 
 impl AOCPart for Part1 {
+    type Parsed = Vec<u32>;
+
     fn new() -> Self {
         Self {}
     }
 
-    fn solve(&mut self, input: &str) -> String {
-        let measurements = retrieve_depth_measurements(input);
-        count_depth_increases(&measurements).to_string()
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        retrieve_depth_measurements(input)
+    }
+
+    fn solve(&mut self, measurements: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(count_depth_increases(measurements).into())
     }
 }
 
 pub struct Part2 {}
 
 impl AOCPart for Part2 {
+    type Parsed = Vec<u32>;
+
     fn new() -> Self {
         Self {}
     }
 
-    fn solve(&mut self, input: &str) -> String {
-        let measurements = retrieve_depth_measurements(input);
-        let measurements = rafine_measurements(&measurements);
-        count_depth_increases(&measurements).to_string()
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        retrieve_depth_measurements(input)
+    }
+
+    fn solve(&mut self, measurements: &Self::Parsed) -> Result<Answer, AocError> {
+        let measurements = rafine_measurements(measurements);
+        Ok(count_depth_increases(&measurements).into())
     }
 }
 
-fn retrieve_depth_measurements(input: &str) -> Vec<u32> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+fn retrieve_depth_measurements(input: &str) -> Result<Vec<u32>, AocError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.parse().map_err(|err| {
+                AocError::at(DAY, index, line, line, format!("invalid depth: {}", err))
+            })
+        })
+        .collect()
 }
 
-fn rafine_measurements(measurements: &[u32]) -> Vec<u32> {
+/// Sums are `u64`, three depths of any `u32` value do not overflow them.
+fn rafine_measurements(measurements: &[u32]) -> Vec<u64> {
     measurements
         .windows(3)
-        .map(|wind| wind.iter().sum())
+        .map(|wind| wind.iter().copied().map(u64::from).sum())
         .collect()
 }
 
-fn count_depth_increases(measurements: &[u32]) -> usize {
+fn count_depth_increases<T: PartialOrd>(measurements: &[T]) -> usize {
     measurements
         .windows(2)
-        .filter(|wind| match wind {
-            [first, second] => second > first,
-            _ => panic!(),
-        })
+        .filter(|wind| wind[1] > wind[0])
         .count()
 }
 
+/// Reference for part 1, comparing each measurement with the one before it. `None` when
+/// `input` is not a list of depths.
+pub(crate) fn reference_part1(input: &str) -> Option<Answer> {
+    let depths = parse_depths(input)?;
+    let mut increases: u64 = 0;
+    for index in 1..depths.len() {
+        if depths[index] > depths[index - 1] {
+            increases += 1;
+        }
+    }
+    Some(increases.into())
+}
+
+/// Reference for part 2, summing both windows in full.
+pub(crate) fn reference_part2(input: &str) -> Option<Answer> {
+    let depths = parse_depths(input)?;
+    let mut increases: u64 = 0;
+    for index in 3..depths.len() {
+        let previous = depths[index - 3] + depths[index - 2] + depths[index - 1];
+        let current = depths[index - 2] + depths[index - 1] + depths[index];
+        if current > previous {
+            increases += 1;
+        }
+    }
+    Some(increases.into())
+}
+
+fn parse_depths(input: &str) -> Option<Vec<u64>> {
+    input.lines().map(|line| line.parse().ok()).collect()
+}
+
+pub(crate) const REPL_HELPERS: &[Helper] = &[
+    Helper {
+        name: "retrieve_depth_measurements",
+        params: "input",
+        call: |args| {
+            retrieve_depth_measurements(&args.get::<String>(0)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+    Helper {
+        name: "rafine_measurements",
+        params: "measurements",
+        call: |args| Ok(rafine_measurements(&args.get::<Vec<u32>>(0)?).into_value()),
+    },
+    Helper {
+        name: "count_depth_increases",
+        params: "measurements",
+        call: |args| Ok(count_depth_increases(&args.get::<Vec<u64>>(0)?).into_value()),
+    },
+];
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::rng::{self, Rng};
+
+    #[test]
+    fn retrieve_depth_measurements_example() {
+        assert_eq!(
+            retrieve_depth_measurements("199\n200\n208").unwrap(),
+            vec![199, 200, 208]
+        );
+        assert_eq!(
+            retrieve_depth_measurements("199\n2OO\n208")
+                .unwrap_err()
+                .to_string(),
+            "2021 day 1 line 2 column 1: invalid depth: invalid digit found in string"
+        );
+    }
 
     #[test]
     fn count_depth_increases_example() {
@@ -67,4 +154,53 @@ mod test {
             vec![607, 618, 618, 617, 647, 716, 769, 792]
         );
     }
+
+    #[test]
+    fn large_depths_do_not_overflow() {
+        let depths = "4000000000\n4000000000\n4000000000\n4000000001\n";
+        assert_eq!(Part1::new().run(depths), Ok(1.into()));
+        assert_eq!(Part2::new().run(depths), Ok(1.into()));
+    }
+
+    fn measurements(rng: &mut Rng) -> Vec<u32> {
+        (0..rng.below(50))
+            .map(|_| rng.range(0..10_000) as u32)
+            .collect()
+    }
+
+    #[test]
+    fn rafine_measurements_properties() {
+        rng::check(200, |rng| {
+            let measurements = measurements(rng);
+            let rafined = rafine_measurements(&measurements);
+            assert_eq!(rafined.len(), measurements.len().saturating_sub(2));
+            for (index, sum) in rafined.iter().enumerate() {
+                assert_eq!(
+                    *sum,
+                    measurements[index..index + 3]
+                        .iter()
+                        .map(|&depth| u64::from(depth))
+                        .sum::<u64>()
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn count_depth_increases_properties() {
+        rng::check(200, |rng| {
+            let mut measurements = measurements(rng);
+            let increases = count_depth_increases(&measurements);
+            assert!(increases <= measurements.len().saturating_sub(1));
+
+            measurements.sort_unstable();
+            measurements.dedup();
+            assert_eq!(
+                count_depth_increases(&measurements),
+                measurements.len().saturating_sub(1)
+            );
+            measurements.reverse();
+            assert_eq!(count_depth_increases(&measurements), 0);
+        });
+    }
 }
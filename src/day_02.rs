@@ -1,31 +1,63 @@
-use crate::aoc::AOCPart;
+use crate::aoc::{AOCPart, Answer, AocError, Location};
+use crate::repl::{native_values, Helper, IntoValue};
 
-#[derive(Debug, PartialEq, Eq)]
+const DAY: u32 = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 enum Direction {
     Forward,
     Down,
     Up,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct Command {
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Command {
     direction: Direction,
-    units: i32,
+    units: i64,
+    /// Where the units are in the input, for overflows found when moving.
+    units_at: Location,
+}
+
+impl Command {
+    fn overflow(&self) -> AocError {
+        AocError::located(DAY, self.units_at.clone(), "the position overflows")
+    }
 }
 
-fn retrieve_command_list(input: &str) -> Vec<Command> {
+fn retrieve_command_list(input: &str) -> Result<Vec<Command>, AocError> {
     input
         .lines()
-        .map(|line| {
+        .enumerate()
+        .map(|(index, line)| {
+            let error = |text: &str, message: String| AocError::at(DAY, index, line, text, message);
+
             let mut words = line.split_whitespace();
-            let direction = match words.next().unwrap() {
-                "forward" => Direction::Forward,
-                "down" => Direction::Down,
-                "up" => Direction::Up,
-                _ => panic!(),
+            let direction = match words.next() {
+                Some("forward") => Direction::Forward,
+                Some("down") => Direction::Down,
+                Some("up") => Direction::Up,
+                Some(word) => return Err(error(word, format!("unknown direction '{}'", word))),
+                None => return Err(error(line, "missing direction".to_string())),
             };
-            let units = words.next().unwrap().parse().unwrap();
-            Command { direction, units }
+            let (units, units_at) = match words.next() {
+                Some(word) => (
+                    word.parse()
+                        .map_err(|err| error(word, format!("invalid units: {}", err)))?,
+                    Location::new(index, line, word),
+                ),
+                None => return Err(error(&line[line.len()..], "missing units".to_string())),
+            };
+            if let Some(word) = words.next() {
+                return Err(error(
+                    word,
+                    format!("unexpected '{}' after the units", word),
+                ));
+            }
+            Ok(Command {
+                direction,
+                units,
+                units_at,
+            })
         })
         .collect()
 }
@@ -33,160 +65,319 @@ fn retrieve_command_list(input: &str) -> Vec<Command> {
 pub struct Part1 {}
 
 impl AOCPart for Part1 {
+    type Parsed = Vec<Command>;
+
     fn new() -> Self {
         Self {}
     }
 
-    fn solve(&mut self, input: &str) -> String {
-        let commands = retrieve_command_list(input);
-        let (depth, horizontal) = run_commands(&commands);
-        (depth * horizontal).to_string()
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        retrieve_command_list(input)
+    }
+
+    fn solve(&mut self, commands: &Self::Parsed) -> Result<Answer, AocError> {
+        let (depth, horizontal) = run_commands(commands)?;
+        position_product(depth, horizontal)
     }
 }
 
-fn run_commands(commands: &[Command]) -> (i32, i32) {
+fn run_commands(commands: &[Command]) -> Result<(i64, i64), AocError> {
     commands
         .iter()
-        .fold((0, 0), |(depth, horizontal), command| {
+        .try_fold((0i64, 0i64), |(depth, horizontal), command| {
             match command.direction {
-                Direction::Down => (depth + command.units, horizontal),
-                Direction::Up => (depth - command.units, horizontal),
-                Direction::Forward => (depth, horizontal + command.units),
+                Direction::Down => depth
+                    .checked_add(command.units)
+                    .map(|depth| (depth, horizontal)),
+                Direction::Up => depth
+                    .checked_sub(command.units)
+                    .map(|depth| (depth, horizontal)),
+                Direction::Forward => horizontal
+                    .checked_add(command.units)
+                    .map(|horizontal| (depth, horizontal)),
             }
+            .ok_or_else(|| command.overflow())
         })
 }
 
+/// Computed on `i128`, where the product of two `i64` always fits. Only a negative product
+/// out of the range of `i64` cannot be an answer.
+fn position_product(depth: i64, horizontal: i64) -> Result<Answer, AocError> {
+    let product = depth as i128 * horizontal as i128;
+    if let Ok(product) = u128::try_from(product) {
+        return Ok(product.into());
+    }
+    i64::try_from(product).map(Answer::from).map_err(|_| {
+        AocError::new(
+            DAY,
+            format!(
+                "depth {} times horizontal position {} is out of range",
+                depth, horizontal
+            ),
+        )
+    })
+}
+
 pub struct Part2 {}
 
 impl AOCPart for Part2 {
+    type Parsed = Vec<Command>;
+
     fn new() -> Self {
         Self {}
     }
 
-    fn solve(&mut self, input: &str) -> String {
-        let commands = retrieve_command_list(input);
-        let (depth, horizontal, _) = run_commands_with_aim(&commands);
-        (depth * horizontal).to_string()
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        retrieve_command_list(input)
+    }
+
+    fn solve(&mut self, commands: &Self::Parsed) -> Result<Answer, AocError> {
+        let (depth, horizontal, _) = run_commands_with_aim(commands)?;
+        position_product(depth, horizontal)
     }
 }
 
-fn run_commands_with_aim(commands: &[Command]) -> (i32, i32, i32) {
-    commands.iter().fold(
-        (0, 0, 0),
-        |(depth, horizontal, aim), command| match command.direction {
-            Direction::Down => (depth, horizontal, aim + command.units),
-            Direction::Up => (depth, horizontal, aim - command.units),
-            Direction::Forward => (depth + aim * command.units, horizontal + command.units, aim),
-        },
-    )
+fn run_commands_with_aim(commands: &[Command]) -> Result<(i64, i64, i64), AocError> {
+    commands
+        .iter()
+        .try_fold((0i64, 0i64, 0i64), |(depth, horizontal, aim), command| {
+            match command.direction {
+                Direction::Down => aim
+                    .checked_add(command.units)
+                    .map(|aim| (depth, horizontal, aim)),
+                Direction::Up => aim
+                    .checked_sub(command.units)
+                    .map(|aim| (depth, horizontal, aim)),
+                Direction::Forward => aim
+                    .checked_mul(command.units)
+                    .and_then(|dive| depth.checked_add(dive))
+                    .zip(horizontal.checked_add(command.units))
+                    .map(|(depth, horizontal)| (depth, horizontal, aim)),
+            }
+            .ok_or_else(|| command.overflow())
+        })
+}
+
+/// Reference for part 1, moving the submarine one command at a time. `None` when `input` is
+/// not a list of commands.
+pub(crate) fn reference_part1(input: &str) -> Option<Answer> {
+    let mut horizontal: i64 = 0;
+    let mut depth: i64 = 0;
+    for (direction, units) in parse_moves(input)? {
+        match direction {
+            "forward" => horizontal += units,
+            "down" => depth += units,
+            "up" => depth -= units,
+            _ => return None,
+        }
+    }
+    Some((horizontal * depth).into())
 }
 
+/// Reference for part 2, where up and down only turn the submarine.
+pub(crate) fn reference_part2(input: &str) -> Option<Answer> {
+    let mut horizontal: i64 = 0;
+    let mut depth: i64 = 0;
+    let mut aim: i64 = 0;
+    for (direction, units) in parse_moves(input)? {
+        match direction {
+            "forward" => {
+                horizontal += units;
+                depth += aim * units;
+            }
+            "down" => aim += units,
+            "up" => aim -= units,
+            _ => return None,
+        }
+    }
+    Some((horizontal * depth).into())
+}
+
+fn parse_moves(input: &str) -> Option<Vec<(&str, i64)>> {
+    input
+        .lines()
+        .map(|line| {
+            let (direction, units) = line.split_once(' ')?;
+            Some((direction, units.parse().ok()?))
+        })
+        .collect()
+}
+
+native_values!(Command);
+
+pub(crate) const REPL_HELPERS: &[Helper] = &[
+    Helper {
+        name: "retrieve_command_list",
+        params: "input",
+        call: |args| {
+            retrieve_command_list(&args.get::<String>(0)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+    Helper {
+        name: "run_commands",
+        params: "commands",
+        call: |args| {
+            run_commands(&args.get::<Vec<Command>>(0)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+    Helper {
+        name: "run_commands_with_aim",
+        params: "commands",
+        call: |args| {
+            run_commands_with_aim(&args.get::<Vec<Command>>(0)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+];
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::rng::{self, Rng};
 
-    #[test]
-    fn retrieve_command_list_example() {
-        assert_eq!(
-            retrieve_command_list(
-                "forward 5
+    const EXAMPLE: &str = "forward 5
 down 5
 forward 8
 up 3
 down 8
-forward 2"
-            ),
+forward 2";
+
+    #[test]
+    fn retrieve_command_list_example() {
+        let commands = retrieve_command_list(EXAMPLE).unwrap();
+        let moves: Vec<(Direction, i64)> = commands
+            .iter()
+            .map(|command| (command.direction.clone(), command.units))
+            .collect();
+        assert_eq!(
+            moves,
             vec![
-                Command {
-                    direction: Direction::Forward,
-                    units: 5
-                },
-                Command {
-                    direction: Direction::Down,
-                    units: 5
-                },
-                Command {
-                    direction: Direction::Forward,
-                    units: 8
-                },
-                Command {
-                    direction: Direction::Up,
-                    units: 3
-                },
-                Command {
-                    direction: Direction::Down,
-                    units: 8
-                },
-                Command {
-                    direction: Direction::Forward,
-                    units: 2
-                }
+                (Direction::Forward, 5),
+                (Direction::Down, 5),
+                (Direction::Forward, 8),
+                (Direction::Up, 3),
+                (Direction::Down, 8),
+                (Direction::Forward, 2)
             ]
         );
+        assert_eq!(
+            commands[2].units_at,
+            Location {
+                line: 3,
+                column: 9,
+                text: "8".to_string(),
+                source_line: "forward 8".to_string(),
+            }
+        );
     }
 
     #[test]
-    fn run_commands_example() {
+    fn retrieve_command_list_errors() {
+        let error = retrieve_command_list("forward 5\nsideways 3").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2021 day 2 line 2 column 1: unknown direction 'sideways'"
+        );
+        assert_eq!(error.location.unwrap().text, "sideways");
+
+        assert_eq!(
+            retrieve_command_list("down x").unwrap_err().to_string(),
+            "2021 day 2 line 1 column 6: invalid units: invalid digit found in string"
+        );
+        assert_eq!(
+            retrieve_command_list("up").unwrap_err().to_string(),
+            "2021 day 2 line 1 column 3: missing units"
+        );
+        let error = retrieve_command_list("forward 5 junk").unwrap_err();
         assert_eq!(
-            run_commands(&[
-                Command {
-                    direction: Direction::Forward,
-                    units: 5
-                },
-                Command {
-                    direction: Direction::Down,
-                    units: 5
-                },
-                Command {
-                    direction: Direction::Forward,
-                    units: 8
-                },
-                Command {
-                    direction: Direction::Up,
-                    units: 3
-                },
-                Command {
-                    direction: Direction::Down,
-                    units: 8
-                },
-                Command {
-                    direction: Direction::Forward,
-                    units: 2
-                }
-            ]),
-            (10, 15)
+            error.to_string(),
+            "2021 day 2 line 1 column 11: unexpected 'junk' after the units"
         );
+        assert_eq!(error.location.unwrap().text, "junk");
+    }
+
+    #[test]
+    fn run_commands_example() {
+        let commands = retrieve_command_list(EXAMPLE).unwrap();
+        assert_eq!(run_commands(&commands), Ok((10, 15)));
     }
 
     #[test]
     fn run_commands_with_aim_example() {
-        let (depth, horizontal, _) = run_commands_with_aim(&[
-            Command {
-                direction: Direction::Forward,
-                units: 5,
-            },
-            Command {
-                direction: Direction::Down,
-                units: 5,
-            },
-            Command {
-                direction: Direction::Forward,
-                units: 8,
-            },
-            Command {
-                direction: Direction::Up,
-                units: 3,
-            },
-            Command {
-                direction: Direction::Down,
-                units: 8,
-            },
-            Command {
-                direction: Direction::Forward,
-                units: 2,
-            },
-        ]);
+        let commands = retrieve_command_list(EXAMPLE).unwrap();
+        let (depth, horizontal, _) = run_commands_with_aim(&commands).unwrap();
         assert_eq!(depth, 60);
         assert_eq!(horizontal, 15);
     }
+
+    #[test]
+    fn large_units_are_errors_instead_of_overflows() {
+        let max = i64::MAX;
+        assert_eq!(
+            Part1::new().run(&format!("forward {}\ndown 2", max)),
+            Ok(Answer::BigUnsigned(2 * max as u128))
+        );
+        let error = Part1::new()
+            .run(&format!("down {}\ndown 1", max))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2021 day 2 line 2 column 6: the position overflows"
+        );
+        assert_eq!(error.location.unwrap().text, "1");
+        assert_eq!(
+            Part2::new()
+                .run(&format!("down 2\nforward {}", max))
+                .unwrap_err()
+                .to_string(),
+            "2021 day 2 line 2 column 9: the position overflows"
+        );
+        assert_eq!(
+            Part1::new()
+                .run(&format!("forward {}\nup {}\nup 1", max, max))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "2021 day 2: depth {} times horizontal position {} is out of range",
+                -max - 1,
+                max
+            )
+        );
+    }
+
+    fn commands(rng: &mut Rng) -> Vec<Command> {
+        let input: String = (0..rng.below(40))
+            .map(|_| {
+                let direction = ["forward", "down", "up"][rng.below(3)];
+                format!("{} {}\n", direction, rng.range(1..10))
+            })
+            .collect();
+        retrieve_command_list(&input).unwrap()
+    }
+
+    #[test]
+    fn run_commands_is_order_independent() {
+        rng::check(200, |rng| {
+            let mut commands = commands(rng);
+            let position = run_commands(&commands).unwrap();
+            rng.shuffle(&mut commands);
+            assert_eq!(run_commands(&commands), Ok(position));
+        });
+    }
+
+    #[test]
+    fn run_commands_with_aim_properties() {
+        rng::check(200, |rng| {
+            let commands = commands(rng);
+            let (depth, horizontal) = run_commands(&commands).unwrap();
+            let (_, aimed_horizontal, aim) = run_commands_with_aim(&commands).unwrap();
+            // the aim follows what the depth did without it
+            assert_eq!((aim, aimed_horizontal), (depth, horizontal));
+        });
+    }
 }
@@ -1,80 +1,138 @@
-use crate::aoc::AOCPart;
+use crate::aoc::{AOCPart, Answer, AocError};
+use crate::repl::{Helper, IntoValue};
+
+const DAY: u32 = 3;
+
 pub struct Part1 {}
 
 impl AOCPart for Part1 {
+    type Parsed = ConsumptionReport;
+
     fn new() -> Self {
         Self {}
     }
 
-    fn solve(&mut self, input: &str) -> String {
-        let comsumption_report = retrieve_consumption(input);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        retrieve_consumption(input)
+    }
+
+    fn solve(&mut self, comsumption_report: &Self::Parsed) -> Result<Answer, AocError> {
         let gamma = calc_gamma(&comsumption_report.report, comsumption_report.mask_size);
         let epsylon = calc_epsylon(&comsumption_report.report, comsumption_report.mask_size);
-        (gamma * epsylon).to_string()
+        Ok((u64::from(gamma) * u64::from(epsylon)).into())
     }
 }
 
 pub struct Part2 {}
 
 impl AOCPart for Part2 {
+    type Parsed = ConsumptionReport;
+
     fn new() -> Self {
         Self {}
     }
 
-    fn solve(&mut self, input: &str) -> String {
-        let comsumption_report = retrieve_consumption(input);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        retrieve_consumption(input)
+    }
+
+    fn solve(&mut self, comsumption_report: &Self::Parsed) -> Result<Answer, AocError> {
         let oxygen = calc_oxygen_generator_rating(
             comsumption_report.report.clone(),
             comsumption_report.mask_size,
             0,
-        );
-        let co2 =
-            calc_co2_scrubber_rating(comsumption_report.report, comsumption_report.mask_size, 0);
-        (oxygen * co2).to_string()
+        )?;
+        let co2 = calc_co2_scrubber_rating(
+            comsumption_report.report.clone(),
+            comsumption_report.mask_size,
+            0,
+        )?;
+        Ok((u64::from(oxygen) * u64::from(co2)).into())
     }
 }
 
-struct ConsumptionReport {
+#[derive(Debug)]
+pub struct ConsumptionReport {
     report: Vec<u32>,
     mask_size: u32,
 }
 
-fn retrieve_consumption(input: &str) -> ConsumptionReport {
-    ConsumptionReport {
-        report: input
-            .lines()
-            .map(|line| u32::from_str_radix(line, 2).unwrap())
-            .collect(),
-        mask_size: input.lines().next().unwrap().len() as u32,
-    }
+fn retrieve_consumption(input: &str) -> Result<ConsumptionReport, AocError> {
+    let mask_size = match input.lines().next() {
+        Some(line) => line.len() as u32,
+        None => return Err(AocError::new(DAY, "empty report")),
+    };
+    let report = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            if line.len() as u32 != mask_size {
+                return Err(AocError::at(
+                    DAY,
+                    index,
+                    line,
+                    line,
+                    format!("line has {} bits, expected {}", line.len(), mask_size),
+                ));
+            }
+            u32::from_str_radix(line, 2).map_err(|err| {
+                AocError::at(
+                    DAY,
+                    index,
+                    line,
+                    line,
+                    format!("invalid binary number: {}", err),
+                )
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(ConsumptionReport { report, mask_size })
 }
 
 fn calc_epsylon(input: &[u32], mask_size: u32) -> u32 {
-    (0..mask_size).into_iter().fold(0u32, |acc, mask| {
+    (0..mask_size).fold(0u32, |acc, mask| {
         let sum = input
             .iter()
             .map(|elem| (elem & (1 << mask)) >> mask)
             .sum::<u32>();
-        let average_msb = (sum >= input.len() as u32 / 2) as u32;
+        let average_msb = (2 * sum >= input.len() as u32) as u32;
         acc | (average_msb << mask)
     })
 }
 
 fn calc_gamma(input: &[u32], mask_size: u32) -> u32 {
-    (0..mask_size).into_iter().fold(0u32, |acc, mask| {
-        acc | (((input
+    (0..mask_size).fold(0u32, |acc, mask| {
+        let sum = input
             .iter()
             .map(|elem| (elem & (1 << mask)) >> mask)
-            .sum::<u32>()
-            <= input.len() as u32 / 2) as u32)
-            << mask)
+            .sum::<u32>();
+        // the complement of `calc_epsylon`, ties included
+        let average_lsb = (2 * sum < input.len() as u32) as u32;
+        acc | (average_lsb << mask)
     })
 }
 
-fn calc_oxygen_generator_rating(input: Vec<u32>, mask_size: u32, current_bit: u32) -> u32 {
+/// Values still left once every bit is used are all the same, no rating can be picked.
+fn repeated_value(value: u32, mask_size: u32) -> AocError {
+    AocError::new(
+        DAY,
+        format!(
+            "{:0width$b} is repeated in the report, no bit tells the copies apart",
+            value,
+            width = mask_size as usize
+        ),
+    )
+}
+
+fn calc_oxygen_generator_rating(
+    input: Vec<u32>,
+    mask_size: u32,
+    current_bit: u32,
+) -> Result<u32, AocError> {
     match input.len() {
-        0 => panic!("empty input"),
-        1 => input[0],
+        0 => Err(AocError::new(DAY, "empty report")),
+        1 => Ok(input[0]),
+        _ if current_bit == mask_size => Err(repeated_value(input[0], mask_size)),
         _ => {
             let bit_offset = (mask_size - current_bit) - 1;
 
@@ -98,10 +156,15 @@ fn calc_oxygen_generator_rating(input: Vec<u32>, mask_size: u32, current_bit: u3
     }
 }
 
-fn calc_co2_scrubber_rating(input: Vec<u32>, mask_size: u32, current_bit: u32) -> u32 {
+fn calc_co2_scrubber_rating(
+    input: Vec<u32>,
+    mask_size: u32,
+    current_bit: u32,
+) -> Result<u32, AocError> {
     match input.len() {
-        0 => panic!("empty input"),
-        1 => input[0],
+        0 => Err(AocError::new(DAY, "empty report")),
+        1 => Ok(input[0]),
+        _ if current_bit == mask_size => Err(repeated_value(input[0], mask_size)),
         _ => {
             let bit_offset = (mask_size - current_bit) - 1;
 
@@ -112,7 +175,12 @@ fn calc_co2_scrubber_rating(input: Vec<u32>, mask_size: u32, current_bit: u32) -
                 .map(|elem| (elem & mask) >> bit_offset)
                 .sum::<u32>();
 
-            let average_lsb = (input.len() as u32 - masked_bit_sum) > masked_bit_sum;
+            // a bit shared by every remaining value rules none out
+            let average_lsb = match input.len() as u32 - masked_bit_sum {
+                0 => true,
+                _ if masked_bit_sum == 0 => false,
+                zeros => zeros > masked_bit_sum,
+            };
 
             let filtered_input = input
                 .iter()
@@ -125,7 +193,64 @@ fn calc_co2_scrubber_rating(input: Vec<u32>, mask_size: u32, current_bit: u32) -
     }
 }
 
+/// Reference for part 1, counting the characters of each column. A tie makes `1` the most
+/// common bit. `None` when `input` is not a report of lines of the same width.
+pub(crate) fn reference_part1(input: &str) -> Option<Answer> {
+    let lines = parse_report(input)?;
+    let mut gamma: u64 = 0;
+    let mut epsilon: u64 = 0;
+    for column in 0..lines[0].len() {
+        let ones = lines.iter().filter(|line| line[column] == b'1').count();
+        let zeros = lines.len() - ones;
+        gamma = gamma * 2 + u64::from(ones >= zeros);
+        epsilon = epsilon * 2 + u64::from(ones < zeros);
+    }
+    Some((gamma * epsilon).into())
+}
+
+/// Reference for part 2, filtering the lines as text. Also `None` when the filtering does not
+/// end on a single line, which happens when the report repeats a line.
+pub(crate) fn reference_part2(input: &str) -> Option<Answer> {
+    let lines = parse_report(input)?;
+    let oxygen = reference_rating(&lines, |ones, zeros| match ones >= zeros {
+        true => b'1',
+        false => b'0',
+    })?;
+    // a bit shared by every remaining line rules none out
+    let co2 = reference_rating(&lines, |ones, zeros| match (ones, zeros) {
+        (_, 0) => b'1',
+        (0, _) => b'0',
+        (ones, zeros) if zeros <= ones => b'0',
+        _ => b'1',
+    })?;
+    Some((oxygen * co2).into())
+}
+
+fn reference_rating(lines: &[&[u8]], keep: fn(usize, usize) -> u8) -> Option<u64> {
+    let mut remaining = lines.to_vec();
+    let mut column = 0;
+    while remaining.len() > 1 && column < lines[0].len() {
+        let ones = remaining.iter().filter(|line| line[column] == b'1').count();
+        let wanted = keep(ones, remaining.len() - ones);
+        remaining.retain(|line| line[column] == wanted);
+        column += 1;
+    }
+    match remaining[..] {
+        [line] => u64::from_str_radix(std::str::from_utf8(line).ok()?, 2).ok(),
+        _ => None,
+    }
+}
+
+fn parse_report(input: &str) -> Option<Vec<&[u8]>> {
+    let lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let width = lines.first()?.len();
+    let valid = |line: &&[u8]| line.len() == width && line.iter().all(|bit| b"01".contains(bit));
+    // wider reports do not fit the u32 of the solver
+    (width > 0 && width <= 32 && lines.iter().all(valid)).then_some(lines)
+}
+
 use std::fmt;
+#[allow(dead_code)]
 struct BinaryPrinter<'a>(&'a [u32]);
 impl fmt::Binary for BinaryPrinter<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -142,9 +267,72 @@ impl fmt::Binary for BinaryPrinter<'_> {
     }
 }
 
+pub(crate) const REPL_HELPERS: &[Helper] = &[
+    Helper {
+        name: "retrieve_consumption",
+        params: "input",
+        call: |args| {
+            retrieve_consumption(&args.get::<String>(0)?)
+                .map(|report| (report.report, report.mask_size).into_value())
+                .map_err(|err| err.to_string())
+        },
+    },
+    Helper {
+        name: "calc_gamma",
+        params: "report mask_size",
+        call: |args| Ok(calc_gamma(&args.get::<Vec<u32>>(0)?, args.get(1)?).into_value()),
+    },
+    Helper {
+        name: "calc_epsylon",
+        params: "report mask_size",
+        call: |args| Ok(calc_epsylon(&args.get::<Vec<u32>>(0)?, args.get(1)?).into_value()),
+    },
+    Helper {
+        name: "calc_oxygen_generator_rating",
+        params: "report mask_size current_bit",
+        call: |args| {
+            calc_oxygen_generator_rating(args.get(0)?, args.get(1)?, args.get(2)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+    Helper {
+        name: "calc_co2_scrubber_rating",
+        params: "report mask_size current_bit",
+        call: |args| {
+            calc_co2_scrubber_rating(args.get(0)?, args.get(1)?, args.get(2)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+];
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::rng::{self, Rng};
+
+    #[test]
+    fn retrieve_consumption_example() {
+        let report = retrieve_consumption("00100\n11110\n10110").unwrap();
+        assert_eq!(report.report, vec![0b00100, 0b11110, 0b10110]);
+        assert_eq!(report.mask_size, 5);
+
+        assert_eq!(
+            retrieve_consumption("00100\n11120")
+                .unwrap_err()
+                .to_string(),
+            "2021 day 3 line 2 column 1: invalid binary number: invalid digit found in string"
+        );
+        assert_eq!(
+            retrieve_consumption("").unwrap_err().to_string(),
+            "2021 day 3: empty report"
+        );
+        assert_eq!(
+            retrieve_consumption("00100\n1111").unwrap_err().to_string(),
+            "2021 day 3 line 2 column 1: line has 4 bits, expected 5"
+        );
+    }
 
     #[test]
     fn calc_epsylon_example_01() {
@@ -186,6 +374,13 @@ mod test {
         assert_eq!(calc_gamma(&[0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 0, 0,], 0), 0);
     }
 
+    #[test]
+    fn calc_gamma_ties() {
+        // with as many ones as zeros, 1 is the most common bit and 0 the least common
+        assert_eq!(calc_epsylon(&[0b10, 0b01], 2), 0b11);
+        assert_eq!(calc_gamma(&[0b10, 0b01], 2), 0b00);
+    }
+
     #[test]
     fn calc_oxygen_example_01() {
         assert_eq!(
@@ -197,7 +392,7 @@ mod test {
                 5,
                 0
             ),
-            23
+            Ok(23)
         );
     }
 
@@ -212,7 +407,64 @@ mod test {
                 5,
                 0
             ),
-            10
+            Ok(10)
         );
     }
+
+    fn report(rng: &mut Rng, mask_size: u32, len: usize) -> Vec<u32> {
+        (0..len)
+            .map(|_| rng.range(0..1 << mask_size) as u32)
+            .collect()
+    }
+
+    #[test]
+    fn gamma_and_epsylon_are_complements() {
+        rng::check(300, |rng| {
+            let mask_size = rng.range(1..13) as u32;
+            // even lengths make ties likely
+            let len = 1 + rng.below(40);
+            let report = report(rng, mask_size, len);
+            assert_eq!(
+                calc_gamma(&report, mask_size) ^ calc_epsylon(&report, mask_size),
+                (1 << mask_size) - 1,
+                "{:?}",
+                report
+            );
+        });
+    }
+
+    #[test]
+    fn ratings_come_from_the_report() {
+        rng::check(300, |rng| {
+            let mask_size = rng.range(1..13) as u32;
+            let len = 1 + rng.below(30);
+            let mut report = report(rng, mask_size, len);
+            report.sort_unstable();
+            report.dedup();
+            let oxygen = calc_oxygen_generator_rating(report.clone(), mask_size, 0).unwrap();
+            let co2 = calc_co2_scrubber_rating(report.clone(), mask_size, 0).unwrap();
+            assert!(report.contains(&oxygen) && report.contains(&co2));
+            assert!(report.len() == 1 || oxygen != co2, "{:?}", report);
+        });
+    }
+
+    #[test]
+    fn repeated_values_are_errors() {
+        let report = retrieve_consumption("101\n101\n011").unwrap();
+        assert_eq!(
+            Part2::new().solve(&report).unwrap_err().to_string(),
+            "2021 day 3: 101 is repeated in the report, no bit tells the copies apart"
+        );
+        assert_eq!(
+            calc_co2_scrubber_rating(vec![0b10, 0b10], 2, 0),
+            Err(repeated_value(0b10, 2))
+        );
+    }
+
+    #[test]
+    fn empty_reports_are_errors() {
+        let error = Err(AocError::new(DAY, "empty report"));
+        assert_eq!(calc_oxygen_generator_rating(vec![], 5, 0), error);
+        assert_eq!(calc_co2_scrubber_rating(vec![], 5, 0), error);
+    }
 }
@@ -1,18 +1,21 @@
-use crate::aoc::AOCPart;
+use crate::aoc::{AOCPart, Answer, AocError};
+use crate::repl::{native_values, Helper, IntoValue};
 
-#[derive(Debug, PartialEq)]
-struct BingoGame {
+const DAY: u32 = 4;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BingoGame {
     numbers: Vec<u32>,
     boards: Vec<BingoBoard>,
 }
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
 enum BingoNumber {
     Marked(u32),
     Unmarked(u32),
 }
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
 struct BingoBoard {
     bingo_status: BingoStatus,
     bingo_lines: Vec<Vec<BingoNumber>>,
@@ -20,54 +23,103 @@ struct BingoBoard {
 
 #[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
 enum BingoStatus {
-    Bingo(u32), // Bingo! with all Nonmarked values added to one another
+    Bingo(u64), // Bingo! with all Nonmarked values added to one another
     Bango,      // not Bingo
 }
 
-fn retrieve_bingo_game(input: &str) -> BingoGame {
-    let numbers: Vec<u32> = input
-        .lines()
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|number| number.parse().unwrap())
-        .collect();
+fn retrieve_bingo_game(input: &str) -> Result<BingoGame, AocError> {
+    let mut lines = input.lines().enumerate();
 
-    let boards: Vec<BingoBoard> = input
-        .split("\n\n")
-        .skip(1)
-        .map(|board| BingoBoard {
-            bingo_status: BingoStatus::Bango,
-            bingo_lines: board
-                .lines()
-                .map(|board_line| {
-                    board_line
-                        .split_whitespace()
-                        .map(|board_cell| BingoNumber::Unmarked(board_cell.parse().unwrap()))
-                        .collect()
+    let numbers: Vec<u32> = match lines.next() {
+        Some((index, line)) => line
+            .split(',')
+            .map(|number| {
+                number.parse().map_err(|err| {
+                    AocError::at(DAY, index, line, number, format!("invalid number: {}", err))
                 })
-                .collect(),
-        })
-        .collect();
+            })
+            .collect::<Result<_, _>>()?,
+        None => return Err(AocError::new(DAY, "empty input")),
+    };
+
+    let mut boards = Vec::new();
+    let mut bingo_lines: Vec<Vec<BingoNumber>> = Vec::new();
+    for (index, line) in lines {
+        if line.trim().is_empty() {
+            if !bingo_lines.is_empty() {
+                boards.push(BingoBoard {
+                    bingo_status: BingoStatus::Bango,
+                    bingo_lines: std::mem::take(&mut bingo_lines),
+                });
+            }
+            continue;
+        }
 
-    BingoGame { numbers, boards }
+        let bingo_line = line
+            .split_whitespace()
+            .map(|board_cell| {
+                board_cell
+                    .parse()
+                    .map(BingoNumber::Unmarked)
+                    .map_err(|err| {
+                        AocError::at(
+                            DAY,
+                            index,
+                            line,
+                            board_cell,
+                            format!("invalid board number: {}", err),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(first_line) = bingo_lines.first() {
+            if first_line.len() != bingo_line.len() {
+                return Err(AocError::at(
+                    DAY,
+                    index,
+                    line,
+                    line,
+                    format!(
+                        "board line has {} numbers, expected {}",
+                        bingo_line.len(),
+                        first_line.len()
+                    ),
+                ));
+            }
+        }
+        bingo_lines.push(bingo_line);
+    }
+    if !bingo_lines.is_empty() {
+        boards.push(BingoBoard {
+            bingo_status: BingoStatus::Bango,
+            bingo_lines,
+        });
+    }
+
+    Ok(BingoGame { numbers, boards })
 }
 
 pub struct Part1 {}
 
 impl AOCPart for Part1 {
+    type Parsed = BingoGame;
+
     fn new() -> Self {
         Self {}
     }
 
-    fn solve(&mut self, input: &str) -> String {
-        let bingo_game = retrieve_bingo_game(input);
-        Part1::do_bingo_game(bingo_game).to_string()
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        retrieve_bingo_game(input)
+    }
+
+    fn solve(&mut self, bingo_game: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(Part1::do_bingo_game(bingo_game.clone())?.into())
     }
 }
 
 impl Part1 {
-    fn do_bingo_game(mut bingo_game: BingoGame) -> u32 {
+    fn do_bingo_game(mut bingo_game: BingoGame) -> Result<u128, AocError> {
         bingo_game
             .numbers
             .iter()
@@ -81,27 +133,35 @@ impl Part1 {
                             _ => None,
                         },
                     )
-                    .map(|bingo_result| bingo_number * bingo_result)
+                    .map(|bingo_result| score(bingo_result, *bingo_number))
             })
-            .unwrap()
+            .ok_or_else(|| AocError::new(DAY, "no board wins"))
     }
 }
 pub struct Part2 {}
 
 impl AOCPart for Part2 {
+    type Parsed = BingoGame;
+
     fn new() -> Self {
         Self {}
     }
 
-    fn solve(&mut self, input: &str) -> String {
-        let bingo_game = retrieve_bingo_game(input);
-        Part2::do_bingo_game(bingo_game).to_string()
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        retrieve_bingo_game(input)
+    }
+
+    fn solve(&mut self, bingo_game: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(Part2::do_bingo_game(bingo_game.clone())?.into())
     }
 }
 
 impl Part2 {
     // beurk
-    fn do_bingo_game(mut bingo_game: BingoGame) -> u32 {
+    fn do_bingo_game(mut bingo_game: BingoGame) -> Result<u128, AocError> {
+        if bingo_game.boards.is_empty() {
+            return Err(AocError::new(DAY, "no board wins"));
+        }
         bingo_game
             .numbers
             .iter()
@@ -110,16 +170,16 @@ impl Part2 {
                     game_board.incoming_number(*bingo_number);
                 });
 
-                match bingo_game.boards.len() {
-                    0 => panic!("missed bingo"),
-                    1 => {
-                        if let BingoStatus::Bingo(bingo_result) = bingo_game.boards[0].bingo_status
-                        {
-                            return Some(bingo_result * bingo_number);
-                        }
-                    }
-                    _ => (),
-                };
+                // boards winning together last are ranked by their order in the input
+                let all_won = bingo_game
+                    .boards
+                    .iter()
+                    .all(|bingo_board| matches!(bingo_board.bingo_status, BingoStatus::Bingo(_)));
+                if let (true, BingoStatus::Bingo(bingo_result)) =
+                    (all_won, bingo_game.boards[0].bingo_status)
+                {
+                    return Some(score(bingo_result, *bingo_number));
+                }
 
                 // remove all bingo games while waiting to find the last one
                 bingo_game
@@ -131,10 +191,15 @@ impl Part2 {
 
                 None
             })
-            .unwrap()
+            .ok_or_else(|| AocError::new(DAY, "some boards never win"))
     }
 }
 
+/// On `u128`, where the sum of a board's `u32` numbers times a `u32` always fits.
+fn score(bingo_result: u64, bingo_number: u32) -> u128 {
+    u128::from(bingo_result) * u128::from(bingo_number)
+}
+
 impl BingoBoard {
     fn incoming_number(&mut self, incoming_number: u32) -> BingoStatus {
         for (y, line) in self.bingo_lines.iter_mut().enumerate() {
@@ -157,10 +222,10 @@ impl BingoBoard {
         }
     }
 
-    fn calc_bingo_value(&self) -> u32 {
+    fn calc_bingo_value(&self) -> u64 {
         self.bingo_lines.iter().fold(0, |acc, line| {
             acc + line.iter().fold(0, |acc, number| match number {
-                BingoNumber::Unmarked(number) => acc + number,
+                BingoNumber::Unmarked(number) => acc + u64::from(*number),
                 BingoNumber::Marked(_) => acc,
             })
         })
@@ -181,9 +246,158 @@ impl BingoBoard {
     }
 }
 
+/// Reference for part 1, playing every board through the draws on its own. Boards winning on
+/// the same number are ranked by their order in the input. `None` when `input` is not a
+/// game of 5x5 boards without repeated numbers, or when no board wins.
+pub(crate) fn reference_part1(input: &str) -> Option<Answer> {
+    let (draws, boards) = parse_game(input)?;
+    let wins: Vec<(usize, u128)> = boards
+        .iter()
+        .filter_map(|board| reference_win(board, &draws))
+        .collect();
+    let (_, score) = wins.iter().min_by_key(|(turn, _)| *turn)?;
+    Some((*score).into())
+}
+
+/// Reference for part 2, the last board to win, the first one in the input on a tie. `None`
+/// as well when a board never wins.
+pub(crate) fn reference_part2(input: &str) -> Option<Answer> {
+    let (draws, boards) = parse_game(input)?;
+    let wins: Vec<(usize, u128)> = boards
+        .iter()
+        .map(|board| reference_win(board, &draws))
+        .collect::<Option<_>>()?;
+    let last = wins.iter().map(|(turn, _)| *turn).max()?;
+    let (_, score) = wins.iter().find(|(turn, _)| *turn == last)?;
+    Some((*score).into())
+}
+
+type Grid = [[u32; 5]; 5];
+
+/// Index of the draw completing a row or column of `board`, and the score of the board.
+fn reference_win(board: &Grid, draws: &[u32]) -> Option<(usize, u128)> {
+    let mut marked = [[false; 5]; 5];
+    for (turn, &number) in draws.iter().enumerate() {
+        for row in 0..5 {
+            for column in 0..5 {
+                if board[row][column] == number {
+                    marked[row][column] = true;
+                }
+            }
+        }
+        let full_row = (0..5).any(|row| (0..5).all(|column| marked[row][column]));
+        let full_column = (0..5).any(|column| (0..5).all(|row| marked[row][column]));
+        if full_row || full_column {
+            let mut unmarked: u128 = 0;
+            for row in 0..5 {
+                for column in 0..5 {
+                    if !marked[row][column] {
+                        unmarked += u128::from(board[row][column]);
+                    }
+                }
+            }
+            return Some((turn, unmarked * u128::from(number)));
+        }
+    }
+    None
+}
+
+fn parse_game(input: &str) -> Option<(Vec<u32>, Vec<Grid>)> {
+    let mut paragraphs = input.split("\n\n");
+    let draws = paragraphs
+        .next()?
+        .trim()
+        .split(',')
+        .map(|number| number.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    let mut boards = Vec::new();
+    for paragraph in paragraphs.filter(|paragraph| !paragraph.trim().is_empty()) {
+        let lines: Vec<&str> = paragraph.lines().collect();
+        if lines.len() != 5 {
+            return None;
+        }
+        let mut board = [[0; 5]; 5];
+        let mut seen = Vec::new();
+        for (row, line) in lines.iter().enumerate() {
+            let numbers: Vec<&str> = line.split_whitespace().collect();
+            if numbers.len() != 5 {
+                return None;
+            }
+            for (column, number) in numbers.iter().enumerate() {
+                board[row][column] = number.parse().ok()?;
+                if seen.contains(&board[row][column]) {
+                    return None;
+                }
+                seen.push(board[row][column]);
+            }
+        }
+        boards.push(board);
+    }
+    Some((draws, boards))
+}
+
+native_values!(BingoGame, BingoBoard, BingoStatus);
+
+pub(crate) const REPL_HELPERS: &[Helper] = &[
+    Helper {
+        name: "retrieve_bingo_game",
+        params: "input",
+        call: |args| {
+            retrieve_bingo_game(&args.get::<String>(0)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+    Helper {
+        name: "BingoGame::numbers",
+        params: "game",
+        call: |args| Ok(args.get::<BingoGame>(0)?.numbers.into_value()),
+    },
+    Helper {
+        name: "BingoGame::boards",
+        params: "game",
+        call: |args| Ok(args.get::<BingoGame>(0)?.boards.into_value()),
+    },
+    Helper {
+        name: "BingoBoard::incoming_number",
+        params: "board number",
+        call: |args| {
+            let number = args.get(1)?;
+            Ok(args
+                .native_mut::<BingoBoard>(0)?
+                .incoming_number(number)
+                .into_value())
+        },
+    },
+    Helper {
+        name: "BingoBoard::calc_bingo_value",
+        params: "board",
+        call: |args| Ok(args.get::<BingoBoard>(0)?.calc_bingo_value().into_value()),
+    },
+    Helper {
+        name: "Part1::do_bingo_game",
+        params: "game",
+        call: |args| {
+            Part1::do_bingo_game(args.get(0)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+    Helper {
+        name: "Part2::do_bingo_game",
+        params: "game",
+        call: |args| {
+            Part2::do_bingo_game(args.get(0)?)
+                .map(IntoValue::into_value)
+                .map_err(|err| err.to_string())
+        },
+    },
+];
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::rng::{self, Rng};
     use BingoNumber::{Marked, Unmarked};
 
     #[test]
@@ -210,7 +424,8 @@ mod test {
             22 11 13  6  5
              2  0 12  3  7
 "#
-            ),
+            )
+            .unwrap(),
             BingoGame {
                 numbers: vec![
                     7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24, 10, 16, 13, 6, 15, 25, 12, 22, 18,
@@ -342,6 +557,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn retrieve_bingo_errors() {
+        assert_eq!(
+            retrieve_bingo_game("7,4,x,5\n\n1 2\n3 4")
+                .unwrap_err()
+                .to_string(),
+            "2021 day 4 line 1 column 5: invalid number: invalid digit found in string"
+        );
+        assert_eq!(
+            retrieve_bingo_game("7,4\n\n1 2\n3 -4")
+                .unwrap_err()
+                .to_string(),
+            "2021 day 4 line 4 column 3: invalid board number: invalid digit found in string"
+        );
+        assert_eq!(
+            retrieve_bingo_game("7,4\n\n1 2\n3 4 5")
+                .unwrap_err()
+                .to_string(),
+            "2021 day 4 line 4 column 1: board line has 3 numbers, expected 2"
+        );
+    }
+
     #[test]
     fn test_incoming_number() {
         let mut bingo_board = BingoBoard {
@@ -405,59 +642,100 @@ mod test {
         assert_eq!(bingo, BingoStatus::Bingo(26));
     }
 
-    #[test]
-    fn solve_part1_example() {
-        let bingo_game = retrieve_bingo_game(
-            r#"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
-
-            22 13 17 11  0
-             8  2 23  4 24
-            21  9 14 16  7
-             6 10  3 18  5
-             1 12 20 15 19
-
-             3 15  0  2 22
-             9 18 13 17  5
-            19  8  7 25 23
-            20 11 10 24  4
-            14 21 16 12  6
-
-            14 21 17 24  4
-            10 16 15  9 19
-            18  8 23 26 20
-            22 11 13  6  5
-             2  0 12  3  7
-"#,
-        );
+    /// A 5x5 board of distinct numbers below 100.
+    fn board(rng: &mut Rng) -> (BingoBoard, Vec<u32>) {
+        let mut numbers: Vec<u32> = (0..100).collect();
+        rng.shuffle(&mut numbers);
+        numbers.truncate(25);
+        let board = BingoBoard {
+            bingo_status: BingoStatus::Bango,
+            bingo_lines: numbers
+                .chunks(5)
+                .map(|line| {
+                    line.iter()
+                        .map(|&number| BingoNumber::Unmarked(number))
+                        .collect()
+                })
+                .collect(),
+        };
+        (board, numbers)
+    }
 
-        assert_eq!(Part1::do_bingo_game(bingo_game), 4512);
+    #[test]
+    fn calc_bingo_value_sums_unmarked_numbers() {
+        rng::check(300, |rng| {
+            let (mut board, numbers) = board(rng);
+            let mut marked = Vec::new();
+            for _ in 0..rng.below(40) {
+                let number = rng.range(0..100) as u32;
+                let status = board.incoming_number(number);
+                marked.push(number);
+                let unmarked: u64 = numbers
+                    .iter()
+                    .filter(|number| !marked.contains(number))
+                    .map(|&number| u64::from(number))
+                    .sum();
+                assert_eq!(board.calc_bingo_value(), unmarked);
+                if let BingoStatus::Bingo(value) = status {
+                    assert_eq!(value, unmarked);
+                }
+            }
+        });
     }
 
     #[test]
-    fn solve_part2_example() {
-        let bingo_game = retrieve_bingo_game(
-            r#"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+    fn bingo_once_a_line_or_column_is_marked() {
+        rng::check(300, |rng| {
+            let (mut board, numbers) = board(rng);
+            let index = rng.below(5);
+            let mut drawn: Vec<u32> = match rng.chance(1, 2) {
+                true => numbers[index * 5..index * 5 + 5].to_vec(),
+                false => numbers.iter().skip(index).step_by(5).copied().collect(),
+            };
+            rng.shuffle(&mut drawn);
+            let last = drawn.pop().unwrap();
+            for number in drawn {
+                assert_eq!(board.incoming_number(number), BingoStatus::Bango);
+            }
+            assert!(matches!(board.incoming_number(last), BingoStatus::Bingo(_)));
+        });
+    }
 
-            22 13 17 11  0
-             8  2 23  4 24
-            21  9 14 16  7
-             6 10  3 18  5
-             1 12 20 15 19
+    #[test]
+    fn last_boards_winning_together() {
+        // the second and third boards win together on 4, the second one counts
+        let game = retrieve_bingo_game("1,2,3,4\n\n1 2\n8 9\n\n3 4\n8 9\n\n4 5\n3 6").unwrap();
+        assert_eq!(Part2::do_bingo_game(game), Ok(17 * 4));
+    }
 
-             3 15  0  2 22
-             9 18 13 17  5
-            19  8  7 25 23
-            20 11 10 24  4
-            14 21 16 12  6
+    #[test]
+    fn large_numbers_do_not_overflow() {
+        let game = "4000000000\n\n4000000000 5\n";
+        assert_eq!(Part1::new().run(game), Ok(20_000_000_000u64.into()));
+        assert_eq!(Part2::new().run(game), Ok(20_000_000_000u64.into()));
+
+        // the first copy of the drawn number is marked, completing its column
+        let max = u32::MAX;
+        let game = format!("{}\n\n{} {} 3\n", max, max, max);
+        let score = (u128::from(max) + 3) * u128::from(max);
+        assert!(score > u128::from(u64::MAX));
+        assert_eq!(Part1::new().run(&game), Ok(Answer::BigUnsigned(score)));
+    }
 
-            14 21 17 24  4
-            10 16 15  9 19
-            18  8 23 26 20
-            22 11 13  6  5
-             2  0 12  3  7
-"#,
+    #[test]
+    fn boards_that_never_win_are_errors() {
+        let game = retrieve_bingo_game("1,2,3\n\n1 2\n3 4\n\n5 6\n7 8").unwrap();
+        assert_eq!(Part1::do_bingo_game(game.clone()), Ok(7 * 2));
+        assert_eq!(
+            Part2::new().solve(&game).unwrap_err().to_string(),
+            "2021 day 4: some boards never win"
         );
-
-        assert_eq!(Part2::do_bingo_game(bingo_game), 1924);
+        let game = retrieve_bingo_game("9\n\n1 2\n3 4").unwrap();
+        assert_eq!(
+            Part1::new().solve(&game).unwrap_err().to_string(),
+            "2021 day 4: no board wins"
+        );
+        let game = retrieve_bingo_game("1,2").unwrap();
+        assert!(Part2::do_bingo_game(game).is_err());
     }
 }
@@ -0,0 +1,317 @@
+//! Differential testing of the solvers against the simple reference solutions kept in each
+//! day module, on generated inputs.
+//!
+//! A reference returns `None` for inputs outside the puzzle's assumptions, such as a bingo
+//! board that never wins. Such inputs say nothing about the solver and are skipped when
+//! minimizing.
+
+use crate::aoc::Answer;
+use crate::generate;
+use crate::registry::{self, Solver};
+use crate::rng::Rng;
+use crate::runner;
+use crate::{day_01, day_02, day_03, day_04};
+use std::fmt;
+
+pub const DEFAULT_CASES: u64 = 100;
+
+pub struct Reference {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    solve: fn(&str) -> Option<Answer>,
+}
+
+static REFERENCES: &[Reference] = &[
+    Reference {
+        year: 2021,
+        day: 1,
+        part: 1,
+        solve: day_01::reference_part1,
+    },
+    Reference {
+        year: 2021,
+        day: 1,
+        part: 2,
+        solve: day_01::reference_part2,
+    },
+    Reference {
+        year: 2021,
+        day: 2,
+        part: 1,
+        solve: day_02::reference_part1,
+    },
+    Reference {
+        year: 2021,
+        day: 2,
+        part: 2,
+        solve: day_02::reference_part2,
+    },
+    Reference {
+        year: 2021,
+        day: 3,
+        part: 1,
+        solve: day_03::reference_part1,
+    },
+    Reference {
+        year: 2021,
+        day: 3,
+        part: 2,
+        solve: day_03::reference_part2,
+    },
+    Reference {
+        year: 2021,
+        day: 4,
+        part: 1,
+        solve: day_04::reference_part1,
+    },
+    Reference {
+        year: 2021,
+        day: 4,
+        part: 2,
+        solve: day_04::reference_part2,
+    },
+];
+
+pub fn references() -> &'static [Reference] {
+    REFERENCES
+}
+
+/// Every registered solver must have a reference, a missing one is an error rather than a
+/// solver left out of the comparison.
+pub fn find(solver: &Solver) -> Result<&'static Reference, String> {
+    REFERENCES
+        .iter()
+        .find(|reference| {
+            (reference.year, reference.day, reference.part)
+                == (solver.year, solver.day, solver.part)
+        })
+        .ok_or_else(|| {
+            format!(
+                "{} day {} part {} has no reference solution",
+                solver.year, solver.day, solver.part
+            )
+        })
+}
+
+/// An input on which the solver does not give the reference answer.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Seed of the generated input the mismatch was found on.
+    pub seed: u64,
+    pub generated_lines: usize,
+    /// The generated input once minimized.
+    pub input: String,
+    pub expected: Answer,
+    /// The solver error or panic message otherwise.
+    pub actual: Result<Answer, String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "input of seed {}, minimized from {} to {} lines:",
+            self.seed,
+            self.generated_lines,
+            self.input.lines().count()
+        )?;
+        write!(f, "{}", self.input)?;
+        if !self.input.ends_with('\n') {
+            writeln!(f)?;
+        }
+        writeln!(f, "reference: {}", self.expected)?;
+        match &self.actual {
+            Ok(answer) => write!(f, "solver:    {}", answer),
+            Err(err) => write!(f, "solver:    {}", err),
+        }
+    }
+}
+
+impl Reference {
+    fn solver(&self) -> Result<&'static Solver, String> {
+        registry::find(self.year, self.day, self.part).map_err(|err| err.to_string())
+    }
+
+    /// Reference and solver answers on `input` when they differ.
+    fn compare(&self, solver: &Solver, input: &str) -> Option<(Answer, Result<Answer, String>)> {
+        let expected = (self.solve)(input)?;
+        let actual = runner::catch_panic(|| solver.build().run(input))
+            .map_err(|message| format!("PANIC: {}", message))
+            .and_then(|answer| answer.map_err(|err| err.to_string()));
+        match actual {
+            Ok(ref answer) if *answer == expected => None,
+            actual => Some((expected, actual)),
+        }
+    }
+
+    /// Compares the solver with the reference on `cases` generated inputs of up to `max_size`
+    /// records, the size of the real input by default, and minimizes the first mismatch.
+    ///
+    /// Case `n` is generated from `seed + n`, so a mismatch can be replayed with `gen`.
+    pub fn check(
+        &self,
+        cases: u64,
+        max_size: Option<usize>,
+        seed: u64,
+    ) -> Result<Option<Mismatch>, String> {
+        let solver = self.solver()?;
+        let max_size = match max_size {
+            Some(size) => size,
+            None => generate::default_size(self.year, self.day)?,
+        };
+        for case in 0..cases {
+            let seed = seed.wrapping_add(case);
+            let size = 1 + Rng::new(seed).below(max_size.max(1));
+            let input = generate::generate(self.year, self.day, Some(size), seed)?;
+            if (self.solve)(&input).is_none() {
+                return Err(format!(
+                    "the reference rejects the generated input of seed {}",
+                    seed
+                ));
+            }
+            if self.compare(solver, &input).is_some() {
+                let minimized = minimize(&input, |input| self.compare(solver, input).is_some());
+                let (expected, actual) = self.compare(solver, &minimized).unwrap();
+                return Ok(Some(Mismatch {
+                    seed,
+                    generated_lines: input.lines().count(),
+                    input: minimized,
+                    expected,
+                    actual,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Shrinks `input` while it still `fails`, dropping blank line separated blocks first, then
+/// single lines.
+pub fn minimize(input: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    let input = remove_chunks(input, "\n\n", &mut fails);
+    remove_chunks(&input, "\n", &mut fails)
+}
+
+/// Tries removing chunks of `separator` separated units, halving the chunk size down to one.
+fn remove_chunks(input: &str, separator: &str, fails: &mut impl FnMut(&str) -> bool) -> String {
+    let mut units: Vec<&str> = input.split(separator).collect();
+    let mut chunk = units.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < units.len() {
+            let end = (start + chunk).min(units.len());
+            let candidate = [&units[..start], &units[end..]].concat();
+            if fails(&candidate.join(separator)) {
+                units = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+    units.join(separator)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::InputSource;
+
+    #[test]
+    fn references_cover_registered_solvers() {
+        let keys: Vec<(u32, u32, u32)> = references()
+            .iter()
+            .map(|reference| (reference.year, reference.day, reference.part))
+            .collect();
+        let solvers: Vec<(u32, u32, u32)> = registry::solvers()
+            .iter()
+            .map(|solver| (solver.year, solver.day, solver.part))
+            .collect();
+        assert_eq!(keys, solvers);
+    }
+
+    #[test]
+    fn find_reference() {
+        let reference = find(registry::find(2021, 3, 2).unwrap()).unwrap();
+        assert_eq!(
+            (reference.year, reference.day, reference.part),
+            (2021, 3, 2)
+        );
+
+        static UNREFERENCED: Solver = Solver::new(2021, 26, 1, || {
+            Box::new(<day_01::Part1 as crate::aoc::AOCPart>::new())
+        });
+        assert_eq!(
+            find(&UNREFERENCED).err(),
+            Some("2021 day 26 part 1 has no reference solution".to_string())
+        );
+    }
+
+    #[test]
+    fn solvers_agree_with_references() {
+        for reference in references() {
+            if let Some(mismatch) = reference.check(30, Some(30), 0).unwrap() {
+                panic!(
+                    "{} day {} part {}: {}",
+                    reference.year, reference.day, reference.part, mismatch
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn references_solve_examples() {
+        // the day 1 and 2 subjects have no example yet
+        let depths = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        assert_eq!(day_01::reference_part1(depths), Some(7.into()));
+        assert_eq!(day_01::reference_part2(depths), Some(5.into()));
+        let commands = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        assert_eq!(day_02::reference_part1(commands), Some(150.into()));
+        assert_eq!(day_02::reference_part2(commands), Some(900.into()));
+
+        let answers = [198, 230, 4512, 1924];
+        for (reference, answer) in references()[4..].iter().zip(answers) {
+            let input = InputSource::Example(1)
+                .read(reference.year, reference.day)
+                .unwrap();
+            assert_eq!((reference.solve)(&input), Some(answer.into()));
+        }
+    }
+
+    #[test]
+    fn minimize_lines_and_blocks() {
+        let input: String = (1..=20).map(|line| format!("{}\n", line)).collect();
+        let fails = |input: &str| {
+            let lines: Vec<&str> = input.lines().collect();
+            lines.contains(&"7") && lines.contains(&"13")
+        };
+        assert_eq!(minimize(&input, fails), "7\n13");
+
+        let blocks = "0\n\n1 2\n3 4\n\n5 6\n7 8\n\n9 10\n";
+        let fails = |input: &str| input.starts_with("0\n\n") && input.contains("5 6\n7 8");
+        assert_eq!(minimize(blocks, fails), "0\n\n5 6\n7 8");
+    }
+
+    #[test]
+    fn mismatches_are_minimized() {
+        // counts every measurement deeper than the first instead of the previous one
+        let reference = Reference {
+            year: 2021,
+            day: 1,
+            part: 1,
+            solve: |input| {
+                let depths: Vec<u32> = input
+                    .lines()
+                    .map(|line| line.parse().ok())
+                    .collect::<Option<_>>()?;
+                let first = depths.first().copied().unwrap_or(0);
+                Some(depths.iter().filter(|&&depth| depth > first).count().into())
+            },
+        };
+        let mismatch = reference.check(10, Some(50), 0).unwrap().unwrap();
+        assert_eq!(mismatch.input.lines().count(), 3, "{}", mismatch);
+        assert!(mismatch.generated_lines >= 3);
+        assert_ne!(Ok(mismatch.expected), mismatch.actual);
+    }
+}
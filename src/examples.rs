@@ -0,0 +1,162 @@
+use crate::answers::Verdict;
+use crate::registry::{self, Solver};
+use crate::runner;
+use crate::subjects::{subject_path, Subject};
+
+/// A solver run on an example of its subject.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExampleCheck {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    /// Index of the example in the subject, `None` when the subject could not be loaded.
+    pub example: Option<usize>,
+    pub verdict: Verdict,
+}
+
+/// Runs the solver on every example of the subject stating an answer for its part.
+pub fn check_examples(solver: &Solver, subject: &Subject) -> Vec<ExampleCheck> {
+    subject
+        .answered_examples(solver.part)
+        .into_iter()
+        .map(|(index, expected)| {
+            let verdict = match runner::run_solver(solver, &subject.examples[index].input) {
+                Ok(solution) if solution.answer.to_string() == expected => Verdict::Pass,
+                Ok(solution) => Verdict::Fail {
+                    expected: expected.to_string(),
+                    actual: solution.answer.to_string(),
+                },
+                Err(err) => Verdict::Error(err.to_string()),
+            };
+            ExampleCheck {
+                year: solver.year,
+                day: solver.day,
+                part: solver.part,
+                example: Some(index),
+                verdict,
+            }
+        })
+        .collect()
+}
+
+/// Checks every registered solver whose day has a subject, a subject that cannot be loaded
+/// being an error of each of its solvers. Missing and blank subject files are placeholders
+/// for days not transcribed yet.
+pub fn check_all() -> Vec<ExampleCheck> {
+    registry::solvers()
+        .iter()
+        .filter(|solver| {
+            std::fs::read_to_string(subject_path(solver.year, solver.day))
+                .is_ok_and(|text| !text.trim().is_empty())
+        })
+        .flat_map(|solver| check_subject(solver, Subject::load(solver.year, solver.day)))
+        .collect()
+}
+
+fn check_subject(solver: &Solver, subject: Result<Subject, String>) -> Vec<ExampleCheck> {
+    match subject {
+        Ok(subject) => check_examples(solver, &subject),
+        Err(err) => vec![ExampleCheck {
+            year: solver.year,
+            day: solver.day,
+            part: solver.part,
+            example: None,
+            verdict: Verdict::Error(err),
+        }],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::subjects::Example;
+
+    #[test]
+    fn registered_solvers_solve_subject_examples() {
+        let checks = check_all();
+        for check in &checks {
+            assert_eq!(
+                check.verdict,
+                Verdict::Pass,
+                "{} day {} part {}",
+                check.year,
+                check.day,
+                check.part
+            );
+        }
+        for day in [3, 4] {
+            for part in [1, 2] {
+                assert!(checks
+                    .iter()
+                    .any(|check| (check.year, check.day, check.part) == (2021, day, part)));
+            }
+        }
+    }
+
+    #[test]
+    fn check_examples_verdicts() {
+        let example = |input: &str, answers: [Option<&str>; 2]| Example {
+            input: input.to_string(),
+            answers: answers.map(|answer| answer.map(str::to_string)),
+        };
+        let subject = Subject {
+            title: "Sonar Sweep".to_string(),
+            part_one: String::new(),
+            part_two: None,
+            examples: vec![
+                example("199\n200\n208\n210\n200\n", [Some("3"), Some("0")]),
+                example("1\n2\n", [None, None]),
+                example("3\n2\n1\n", [Some("0"), None]),
+            ],
+        };
+        let solver = |part| registry::find(2021, 1, part).unwrap();
+        let verdicts = |part| {
+            check_examples(solver(part), &subject)
+                .into_iter()
+                .map(|check| (check.example, check.verdict))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            verdicts(1),
+            vec![(Some(0), Verdict::Pass), (Some(2), Verdict::Pass)]
+        );
+        assert_eq!(
+            verdicts(2),
+            vec![(
+                Some(0),
+                Verdict::Fail {
+                    expected: "0".to_string(),
+                    actual: "1".to_string()
+                }
+            )]
+        );
+        assert_eq!(
+            check_examples(
+                solver(1),
+                &Subject {
+                    examples: vec![],
+                    ..subject.clone()
+                }
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn unreadable_subjects_are_errors() {
+        assert_eq!(
+            check_subject(
+                registry::find(2021, 1, 2).unwrap(),
+                Err("subjects/2021/day_01.txt: missing title".to_string())
+            ),
+            vec![ExampleCheck {
+                year: 2021,
+                day: 1,
+                part: 2,
+                example: None,
+                verdict: Verdict::Error("subjects/2021/day_01.txt: missing title".to_string()),
+            }]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::http;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already there, nothing was requested.
+    Cached,
+    Downloaded {
+        bytes: usize,
+    },
+}
+
+/// `~/.config/aoc/session`
+pub fn session_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config/aoc/session"))
+}
+
+/// The session cookie, from `AOC_SESSION` or else the session file.
+pub fn session_token() -> Result<String, String> {
+    if let Some(token) = std::env::var(SESSION_VARIABLE)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+    {
+        return Ok(token.trim().to_string());
+    }
+    session_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| {
+            format!(
+                "no session token, set {} or write it to ~/.config/aoc/session",
+                SESSION_VARIABLE
+            )
+        })
+}
+
+pub fn input_url(base_url: &str, year: u32, day: u32) -> String {
+    format!(
+        "{}/{}/day/{}/input",
+        base_url.trim_end_matches('/'),
+        year,
+        day
+    )
+}
+
+/// Empty files, as left by scaffolding, do not count as cached.
+pub fn is_cached(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|meta| meta.len() > 0)
+}
+
+/// Downloads the input of the day to `path` unless it is already there.
+pub fn fetch(year: u32, day: u32, base_url: &str, path: &Path) -> Result<Fetched, String> {
+    if is_cached(path) {
+        return Ok(Fetched::Cached);
+    }
+    download(year, day, base_url, &session_token()?, path)
+}
+
+pub fn download(
+    year: u32,
+    day: u32,
+    base_url: &str,
+    session: &str,
+    path: &Path,
+) -> Result<Fetched, String> {
+    let url = input_url(base_url, year, day);
+    let response = http::get(&url, session)?;
+    if !response.is_success() {
+        return Err(format!(
+            "{} answered {}: {}",
+            url,
+            response.status,
+            response.body.lines().next().unwrap_or("").trim()
+        ));
+    }
+
+    let error = |err: std::io::Error| format!("cannot write '{}': {}", path.display(), err);
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(error)?;
+    }
+    // written aside first, an interrupted download must not look cached
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    std::fs::write(&partial, &response.body).map_err(error)?;
+    std::fs::rename(&partial, path).map_err(error)?;
+    Ok(Fetched::Downloaded {
+        bytes: response.body.len(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("aoc_fetch_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn input_urls() {
+        assert_eq!(
+            input_url("https://adventofcode.com", 2021, 4),
+            "https://adventofcode.com/2021/day/4/input"
+        );
+        assert_eq!(
+            input_url("http://localhost:8000/", 2020, 12),
+            "http://localhost:8000/2020/day/12/input"
+        );
+    }
+
+    #[test]
+    fn download_from_local_server() {
+        let path = temp_path("download").join("day_01.txt");
+        let (base_url, request) =
+            http::serve_once("HTTP/1.1 200 OK\r\nContent-Length: 8\r\n\r\n199\n200\n");
+
+        assert!(!is_cached(&path));
+        assert_eq!(
+            download(2021, 1, &base_url, "token", &path),
+            Ok(Fetched::Downloaded { bytes: 8 })
+        );
+        assert!(request
+            .join()
+            .unwrap()
+            .starts_with("GET /2021/day/1/input HTTP/1.1\r\n"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "199\n200\n");
+
+        // no server is listening anymore, a request would fail
+        assert_eq!(fetch(2021, 1, &base_url, &path), Ok(Fetched::Cached));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn download_errors_leave_no_file() {
+        let path = temp_path("error.txt");
+        let (base_url, _) = http::serve_once(
+            "HTTP/1.1 400 Bad Request\r\nContent-Length: 37\r\n\r\nPuzzle inputs differ by user.  Log in",
+        );
+        assert_eq!(
+            download(2021, 1, &base_url, "expired", &path),
+            Err(format!(
+                "{}/2021/day/1/input answered 400: Puzzle inputs differ by user.  Log in",
+                base_url
+            ))
+        );
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,228 @@
+//! Random-input driver for the day parsers.
+//!
+//! Each target feeds arbitrary text to the `parse` of a day's first part, which is the day's
+//! `retrieve_*` function. Malformed inputs must come back as an `AocError`, never as a panic.
+//! Inputs are mutated from a corpus of the real input and the subject examples.
+//!
+//! The same entry point suits libFuzzer; a cargo-fuzz target only needs
+//! `fuzz_target!(|data: &[u8]| aoc_2021::fuzz::run_input("2021-day_01", data));`.
+
+use crate::input::InputSource;
+use crate::registry::{self, Solver};
+use crate::rng::Rng;
+use crate::runner;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_ITERATIONS: u64 = 10_000;
+pub const ARTIFACTS_DIR: &str = "fuzz/artifacts";
+
+/// Pieces likely to reach the edge cases of a parser.
+const TOKENS: &[&str] = &[
+    "\n",
+    "\n\n",
+    "\r\n",
+    " ",
+    ",",
+    "-",
+    "+",
+    "0",
+    "1",
+    "99999999999999999999",
+    "forward",
+    "down",
+    "up",
+    "é",
+    "\u{0}",
+];
+/// Inputs grow past the corpus by at most this many bytes.
+const MAX_GROWTH: usize = 4096;
+
+pub struct Target {
+    pub solver: &'static Solver,
+}
+
+impl Target {
+    /// `2021-day_04`, also the name of its artifacts directory.
+    pub fn name(&self) -> String {
+        format!("{}-day_{:02}", self.solver.year, self.solver.day)
+    }
+
+    /// Input seeds, the real input first when there is one.
+    pub fn corpus(&self) -> Vec<String> {
+        let (year, day) = (self.solver.year, self.solver.day);
+        let mut corpus: Vec<String> = InputSource::Default.read(year, day).into_iter().collect();
+        corpus.extend(
+            (1..)
+                .map_while(|index| InputSource::Example(index).read(year, day).ok())
+                .collect::<Vec<_>>(),
+        );
+        corpus.push(String::new());
+        corpus
+    }
+
+    /// The panic message if parsing `input` panicked.
+    pub fn run(&self, input: &str) -> Result<(), String> {
+        let part = self.solver.build();
+        runner::catch_panic(|| {
+            let _ = part.parse(input);
+        })
+    }
+}
+
+/// One target per registered day.
+pub fn targets() -> Vec<Target> {
+    registry::solvers()
+        .iter()
+        .filter(|solver| solver.part == 1)
+        .map(|solver| Target { solver })
+        .collect()
+}
+
+/// Entry point for external fuzzers, panics like the parser does.
+pub fn run_input(target: &str, data: &[u8]) {
+    let target = targets()
+        .into_iter()
+        .find(|candidate| candidate.name() == target)
+        .unwrap_or_else(|| panic!("unknown fuzz target '{}'", target));
+    let _ = target.solver.build().parse(&String::from_utf8_lossy(data));
+}
+
+/// Changes `input` by a few random edits, keeping it valid UTF-8.
+pub fn mutate(rng: &mut Rng, input: &str, corpus: &[String]) -> String {
+    let mut bytes = input.as_bytes().to_vec();
+    for _ in 0..1 + rng.below(4) {
+        let at = rng.below(bytes.len() + 1);
+        match rng.below(6) {
+            0 if !bytes.is_empty() => {
+                let end = (at + 1 + rng.below(16)).min(bytes.len());
+                bytes.drain(at.min(end)..end);
+            }
+            1 if at < bytes.len() => bytes[at] ^= 1 << rng.below(8),
+            2 => {
+                let token = TOKENS[rng.below(TOKENS.len())];
+                bytes.splice(at..at, token.bytes());
+            }
+            3 if !bytes.is_empty() => {
+                let start = rng.below(bytes.len());
+                let end = (start + 1 + rng.below(64)).min(bytes.len());
+                let chunk = bytes[start..end].to_vec();
+                bytes.splice(at..at, chunk);
+            }
+            4 => {
+                // splice a line of another seed
+                let other = &corpus[rng.below(corpus.len())];
+                if let Some(line) = other.lines().nth(rng.below(other.lines().count().max(1))) {
+                    bytes.splice(at..at, line.bytes().chain([b'\n']));
+                }
+            }
+            _ => {
+                let random: Vec<u8> = (0..1 + rng.below(8))
+                    .map(|_| rng.range(0..256) as u8)
+                    .collect();
+                bytes.splice(at..at, random);
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Crash {
+    pub input: String,
+    pub message: String,
+}
+
+/// Runs the corpus, then `iterations` mutated inputs, stopping at the first panic.
+pub fn fuzz(target: &Target, iterations: u64, seed: u64) -> Result<u64, Crash> {
+    let corpus = target.corpus();
+    let mut rng = Rng::new(seed);
+    let limit = corpus.iter().map(String::len).max().unwrap_or(0) + MAX_GROWTH;
+    let mut runs = 0;
+    for input in corpus.iter().cloned().chain((0..iterations).map(|_| {
+        let seed = &corpus[rng.below(corpus.len())];
+        let mut input = mutate(&mut rng, seed, &corpus);
+        // stacking mutations reaches further from the seeds
+        while rng.chance(1, 3) && input.len() < limit {
+            input = mutate(&mut rng, &input, &corpus);
+        }
+        input
+    })) {
+        runs += 1;
+        if let Err(message) = target.run(&input) {
+            return Err(Crash { input, message });
+        }
+    }
+    Ok(runs)
+}
+
+/// Keeps a crashing input as `fuzz/artifacts/<target>/crash-<hash>`, the cargo-fuzz layout.
+pub fn save_crash(root: &Path, target: &Target, crash: &Crash) -> Result<PathBuf, String> {
+    let dir = root.join(ARTIFACTS_DIR).join(target.name());
+    let path = dir.join(format!(
+        "crash-{}",
+        runner::format_hash(runner::input_hash(&crash.input))
+    ));
+    let error = |err: std::io::Error| format!("cannot write '{}': {}", path.display(), err);
+    std::fs::create_dir_all(&dir).map_err(error)?;
+    std::fs::write(&path, &crash.input).map_err(error)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn targets_cover_registered_days() {
+        let names: Vec<String> = targets().iter().map(Target::name).collect();
+        assert_eq!(
+            names[..4],
+            ["2021-day_01", "2021-day_02", "2021-day_03", "2021-day_04"]
+        );
+        let corpus = targets()[3].corpus();
+        assert!(corpus.len() >= 3, "input, example and empty seed");
+        assert!(corpus.contains(&String::new()));
+    }
+
+    #[test]
+    fn mutations_stay_close_to_the_seed() {
+        crate::rng::check(200, |rng| {
+            let seed = "199\n200\n208\n";
+            let mutated = mutate(rng, seed, &[seed.to_string()]);
+            assert!(mutated.len() <= seed.len() + 4 * 96, "{:?}", mutated);
+        });
+    }
+
+    #[test]
+    fn parsers_do_not_panic() {
+        for target in targets() {
+            if let Err(crash) = fuzz(&target, 2_000, 0) {
+                panic!(
+                    "{} panicked on {:?}: {}",
+                    target.name(),
+                    crash.input,
+                    crash.message
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn crashes_are_reported_and_saved() {
+        let target = Target {
+            solver: registry::find(2021, 1, 1).unwrap(),
+        };
+        assert_eq!(target.run("1\n2\nx"), Ok(()));
+        run_input("2021-day_01", b"1\n\xff\n");
+
+        let crash = Crash {
+            input: "1\n2\n".to_string(),
+            message: "boom".to_string(),
+        };
+        let root = std::env::temp_dir().join(format!("aoc_fuzz_{}", std::process::id()));
+        let path = save_crash(&root, &target, &crash).unwrap();
+        assert!(path.starts_with(root.join("fuzz/artifacts/2021-day_01")));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,190 @@
+//! Random puzzle inputs, shaped like the real ones but of any size.
+//!
+//! The same year, day, size and seed always give the same input, so a stress run or a
+//! benchmark on a generated input can be repeated.
+
+use crate::rng::Rng;
+use std::collections::HashSet;
+
+pub struct Generator {
+    pub year: u32,
+    pub day: u32,
+    /// Records in the real input: measurements, commands, report lines or boards.
+    pub default_size: usize,
+    generate: fn(&mut Rng, usize) -> String,
+}
+
+static GENERATORS: &[Generator] = &[
+    Generator {
+        year: 2021,
+        day: 1,
+        default_size: 2000,
+        generate: depth_measurements,
+    },
+    Generator {
+        year: 2021,
+        day: 2,
+        default_size: 1000,
+        generate: commands,
+    },
+    Generator {
+        year: 2021,
+        day: 3,
+        default_size: 1000,
+        generate: diagnostic_report,
+    },
+    Generator {
+        year: 2021,
+        day: 4,
+        default_size: 100,
+        generate: bingo_game,
+    },
+];
+
+impl Generator {
+    pub fn generate(&self, size: usize, seed: u64) -> String {
+        (self.generate)(&mut Rng::new(seed), size)
+    }
+}
+
+pub fn generators() -> &'static [Generator] {
+    GENERATORS
+}
+
+fn find(year: u32, day: u32) -> Result<&'static Generator, String> {
+    GENERATORS
+        .iter()
+        .find(|generator| (generator.year, generator.day) == (year, day))
+        .ok_or_else(|| format!("no input generator for {} day {}", year, day))
+}
+
+pub fn default_size(year: u32, day: u32) -> Result<usize, String> {
+    find(year, day).map(|generator| generator.default_size)
+}
+
+/// Input of `size` records for the day, the size of the real input when `None`.
+pub fn generate(year: u32, day: u32, size: Option<usize>, seed: u64) -> Result<String, String> {
+    let generator = find(year, day)?;
+    match size.unwrap_or(generator.default_size) {
+        0 => Err("size must be at least 1".to_string()),
+        size => Ok(generator.generate(size, seed)),
+    }
+}
+
+/// A seabed drifting deeper, with local ups and downs.
+fn depth_measurements(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.range(100..200);
+    let mut input = String::new();
+    for _ in 0..size {
+        input += &format!("{}\n", depth);
+        depth = (depth + rng.range(0..40)).saturating_sub(15);
+    }
+    input
+}
+
+/// Moves that never take the submarine above the surface, as in the real inputs.
+fn commands(rng: &mut Rng, size: usize) -> String {
+    let mut depth = 0;
+    let mut input = String::new();
+    for _ in 0..size {
+        let units = rng.range(1..10);
+        let direction = match rng.below(4) {
+            0 | 1 => "forward",
+            2 if depth >= units => {
+                depth -= units;
+                "up"
+            }
+            _ => {
+                depth += units;
+                "down"
+            }
+        };
+        input += &format!("{} {}\n", direction, units);
+    }
+    input
+}
+
+/// Distinct lines, which the ratings rely on, 12 bits wide like the real report and wider
+/// once 12 bits leave too few values to pick from.
+fn diagnostic_report(rng: &mut Rng, size: usize) -> String {
+    let width = (12..).find(|width| 1 << width >= 4 * size).unwrap();
+    let mut seen = HashSet::new();
+    let mut input = String::new();
+    while seen.len() < size {
+        let value = rng.range(0..1 << width);
+        if seen.insert(value) {
+            input += &format!("{:0width$b}\n", value, width = width);
+        }
+    }
+    input
+}
+
+/// Every number from 0 to 99 is drawn, so every board eventually wins.
+fn bingo_game(rng: &mut Rng, size: usize) -> String {
+    let mut numbers: Vec<u32> = (0..100).collect();
+    rng.shuffle(&mut numbers);
+    let draws: Vec<String> = numbers.iter().map(u32::to_string).collect();
+    let mut input = draws.join(",") + "\n";
+    for _ in 0..size {
+        rng.shuffle(&mut numbers);
+        input += "\n";
+        for row in numbers[..25].chunks(5) {
+            let row: Vec<String> = row.iter().map(|number| format!("{:>2}", number)).collect();
+            input += &(row.join(" ") + "\n");
+        }
+    }
+    input
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn generated_inputs_are_solvable() {
+        for generator in generators() {
+            for seed in 0..5 {
+                let input = generator.generate(1 + seed as usize * 20, seed);
+                for part in [1, 2] {
+                    let solver = registry::find(generator.year, generator.day, part).unwrap();
+                    assert!(
+                        solver.build().run(&input).is_ok(),
+                        "{} day {} part {} with seed {}:\n{}",
+                        generator.year,
+                        generator.day,
+                        part,
+                        seed,
+                        input
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generated_inputs_are_deterministic() {
+        assert_eq!(generate(2021, 2, None, 3), generate(2021, 2, None, 3));
+        assert_ne!(generate(2021, 2, None, 3), generate(2021, 2, None, 4));
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(generate(2021, 1, None, 0).unwrap().lines().count(), 2000);
+        assert_eq!(generate(2021, 3, Some(7), 0).unwrap().lines().count(), 7);
+        let report = generate(2021, 3, Some(5000), 0).unwrap();
+        assert_eq!(report.lines().collect::<HashSet<_>>().len(), 5000);
+        assert!(report.lines().all(|line| line.len() == 15));
+        let bingo = generate(2021, 4, Some(3), 0).unwrap();
+        assert_eq!(bingo.lines().count(), 1 + 3 * 6);
+        assert_eq!(bingo.lines().next().unwrap().split(',').count(), 100);
+        assert_eq!(
+            generate(2021, 1, Some(0), 0),
+            Err("size must be at least 1".to_string())
+        );
+        assert_eq!(
+            generate(2021, 5, None, 0),
+            Err("no input generator for 2021 day 5".to_string())
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const USER_AGENT: &str = "aoc_2021 input tooling";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Url<'a> {
+    host: &'a str,
+    port: u16,
+    path: &'a str,
+}
+
+/// Only plain `http://` urls, `https://` goes through curl.
+fn parse_url(url: &str) -> Result<Url<'_>, String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("unsupported url '{}'", url))?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse()
+                .map_err(|_| format!("invalid port in url '{}'", url))?,
+        ),
+        None => (authority, 80),
+    };
+    if host.is_empty() {
+        return Err(format!("missing host in url '{}'", url));
+    }
+    Ok(Url { host, port, path })
+}
+
+pub fn get(url: &str, session: &str) -> Result<Response, String> {
+    request("GET", url, session, None)
+}
+
+/// Posts an `application/x-www-form-urlencoded` body.
+pub fn post_form(url: &str, session: &str, form: &[(&str, &str)]) -> Result<Response, String> {
+    let body = form
+        .iter()
+        .map(|(key, value)| format!("{}={}", url_encode(key), url_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    request("POST", url, session, Some(&body))
+}
+
+fn request(method: &str, url: &str, session: &str, body: Option<&str>) -> Result<Response, String> {
+    if url.starts_with("https://") {
+        curl(method, url, session, body)
+    } else {
+        plain_http(method, url, session, body)
+    }
+}
+
+fn plain_http(
+    method: &str,
+    url: &str,
+    session: &str,
+    body: Option<&str>,
+) -> Result<Response, String> {
+    let target = parse_url(url)?;
+    let error = |err: std::io::Error| format!("{} {}: {}", method, url, err);
+
+    let mut stream = TcpStream::connect((target.host, target.port)).map_err(error)?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(error)?;
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\nConnection: close\r\n",
+        method, target.path, target.host, USER_AGENT, session
+    );
+    if let Some(body) = body {
+        request.push_str(&format!(
+            "Content-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\n",
+            body.len()
+        ));
+    }
+    request.push_str("\r\n");
+    request.push_str(body.unwrap_or(""));
+    stream.write_all(request.as_bytes()).map_err(error)?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).map_err(error)?;
+    parse_response(&String::from_utf8_lossy(&raw))
+        .ok_or_else(|| format!("{} {}: malformed response", method, url))
+}
+
+fn parse_response(raw: &str) -> Option<Response> {
+    let (head, body) = raw.split_once("\r\n\r\n")?;
+    let mut lines = head.lines();
+    let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+    let chunked = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.eq_ignore_ascii_case("transfer-encoding") && value.trim() == "chunked"
+        })
+    });
+    let body = if chunked {
+        dechunk(body)?
+    } else {
+        body.to_string()
+    };
+    Some(Response { status, body })
+}
+
+fn dechunk(mut body: &str) -> Option<String> {
+    let mut decoded = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n")?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.push_str(rest.get(..size)?);
+        body = rest.get(size..)?.strip_prefix("\r\n")?;
+    }
+}
+
+/// The session is handed over on stdin so that it does not show up in the process list.
+fn curl(method: &str, url: &str, session: &str, body: Option<&str>) -> Result<Response, String> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--config", "-"])
+        .args(["--request", method, "--user-agent", USER_AGENT])
+        .args(["--write-out", "\n%{http_code}"]);
+    if let Some(body) = body {
+        command.args(["--data", body]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("cannot run curl: {}", err))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "header = \"Cookie: session={}\"", session)
+            .map_err(|err| format!("cannot run curl: {}", err))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("cannot run curl: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} {}: {}",
+            method,
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout
+        .rsplit_once('\n')
+        .ok_or_else(|| format!("{} {}: malformed curl output", method, url))?;
+    Ok(Response {
+        status: status
+            .trim()
+            .parse()
+            .map_err(|_| format!("{} {}: malformed curl output", method, url))?,
+        body: body.to_string(),
+    })
+}
+
+fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Serves `response` to a single connection on localhost, returning the base url and a
+/// handle yielding the raw request.
+#[cfg(test)]
+pub fn serve_once(response: &str) -> (String, std::thread::JoinHandle<String>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let response = response.to_string();
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        loop {
+            let read = stream.read(&mut buffer).unwrap();
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Content-Length: "))
+                    .map_or(0, |length| length.parse().unwrap());
+                if body.len() >= length {
+                    break;
+                }
+            }
+            if read == 0 {
+                break;
+            }
+        }
+        stream.write_all(response.as_bytes()).unwrap();
+        String::from_utf8(request).unwrap()
+    });
+    (base_url, handle)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_urls() {
+        assert_eq!(
+            parse_url("http://localhost:8080/2021/day/1/input"),
+            Ok(Url {
+                host: "localhost",
+                port: 8080,
+                path: "/2021/day/1/input"
+            })
+        );
+        assert_eq!(
+            parse_url("http://example.com"),
+            Ok(Url {
+                host: "example.com",
+                port: 80,
+                path: "/"
+            })
+        );
+        assert!(parse_url("ftp://example.com").is_err());
+        assert!(parse_url("http://example.com:http/").is_err());
+    }
+
+    #[test]
+    fn parse_responses() {
+        assert_eq!(
+            parse_response("HTTP/1.1 404 Not Found\r\nContent-Length: 3\r\n\r\nno!"),
+            Some(Response {
+                status: 404,
+                body: "no!".to_string()
+            })
+        );
+        assert_eq!(
+            parse_response(
+                "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n1\n2\n\r\n2\r\n3\n\r\n0\r\n\r\n"
+            ),
+            Some(Response {
+                status: 200,
+                body: "1\n2\n3\n".to_string()
+            })
+        );
+        assert_eq!(parse_response("garbage"), None);
+    }
+
+    #[test]
+    fn url_encode_form_values() {
+        assert_eq!(url_encode("a-b_c.d~1"), "a-b_c.d~1");
+        assert_eq!(url_encode("1 2&x=#"), "1%202%26x%3D%23");
+    }
+
+    #[test]
+    fn get_and_post_against_local_server() {
+        let (base_url, request) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n1\n2\n3");
+        assert_eq!(
+            get(&format!("{}/2021/day/1/input", base_url), "secret"),
+            Ok(Response {
+                status: 200,
+                body: "1\n2\n3".to_string()
+            })
+        );
+        let request = request.join().unwrap();
+        assert!(request.starts_with("GET /2021/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains("\r\nCookie: session=secret\r\n"));
+
+        let (base_url, request) = serve_once("HTTP/1.1 200 OK\r\n\r\nthanks");
+        let response = post_form(&base_url, "secret", &[("level", "1"), ("answer", "42")]);
+        assert_eq!(response.unwrap().body, "thanks");
+        let request = request.join().unwrap();
+        assert!(request.starts_with("POST / HTTP/1.1\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=1&answer=42"));
+    }
+}
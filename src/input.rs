@@ -0,0 +1,116 @@
+use crate::subjects;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Where the puzzle input of a run comes from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InputSource {
+    /// `inputs/YYYY/day_XX.txt` for the selected day.
+    Default,
+    File(PathBuf),
+    Stdin,
+    /// The n-th (starting at 1) example block of `subjects/YYYY/day_XX.txt`.
+    Example(usize),
+}
+
+pub fn input_path(year: u32, day: u32) -> PathBuf {
+    PathBuf::from(format!("inputs/{}/day_{:02}.txt", year, day))
+}
+
+impl InputSource {
+    /// `-` means stdin, anything else is a file path.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        }
+    }
+
+    /// Path shown in reports, examples are suffixed with their index.
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        match self {
+            InputSource::Default => input_path(year, day),
+            InputSource::File(path) => path.clone(),
+            InputSource::Stdin => PathBuf::from("-"),
+            InputSource::Example(index) => {
+                let mut path = subjects::subject_path(year, day).into_os_string();
+                path.push(format!("#example-{}", index));
+                PathBuf::from(path)
+            }
+        }
+    }
+
+    pub fn read(&self, year: u32, day: u32) -> Result<String, String> {
+        match self {
+            InputSource::Default => read_file(&input_path(year, day)),
+            InputSource::File(path) => read_file(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|err| format!("cannot read stdin: {}", err))?;
+                Ok(input)
+            }
+            InputSource::Example(index) => {
+                let path = subjects::subject_path(year, day);
+                let examples = subjects::example_blocks(&read_file(&path)?);
+                index
+                    .checked_sub(1)
+                    .and_then(|index| examples.into_iter().nth(index))
+                    .ok_or_else(|| format!("no example {} in '{}'", index, path.display()))
+            }
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map_err(|err| format!("cannot read '{}': {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn input_path_is_zero_padded() {
+        assert_eq!(input_path(2021, 3), PathBuf::from("inputs/2021/day_03.txt"));
+        assert_eq!(
+            input_path(2020, 12),
+            PathBuf::from("inputs/2020/day_12.txt")
+        );
+    }
+
+    #[test]
+    fn source_paths() {
+        assert_eq!(InputSource::from_arg("-"), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg("my_input.txt").path(2021, 4),
+            PathBuf::from("my_input.txt")
+        );
+        assert_eq!(
+            InputSource::Default.path(2021, 4),
+            PathBuf::from("inputs/2021/day_04.txt")
+        );
+        assert_eq!(
+            InputSource::Example(2).path(2021, 4),
+            PathBuf::from("subjects/2021/day_04.txt#example-2")
+        );
+    }
+
+    #[test]
+    fn read_example() {
+        let example = InputSource::Example(1).read(2021, 4).unwrap();
+        assert!(example.starts_with("7,4,9,5,11,17,23,2,0,14,21,24"));
+        assert!(example.ends_with(" 2  0 12  3  7\n"));
+
+        assert_eq!(
+            InputSource::Example(0).read(2021, 4),
+            Err("no example 0 in 'subjects/2021/day_04.txt'".to_string())
+        );
+        assert_eq!(
+            InputSource::Example(2).read(2021, 4),
+            Err("no example 2 in 'subjects/2021/day_04.txt'".to_string())
+        );
+    }
+}
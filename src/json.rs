@@ -0,0 +1,58 @@
+//! Just enough JSON writing for the runner output, without pulling in serde.
+
+pub fn string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+pub fn array<I: IntoIterator<Item = String>>(values: I) -> String {
+    format!("[{}]", values.into_iter().collect::<Vec<_>>().join(","))
+}
+
+/// Builds an object from already serialized values, keeping the keys in insertion order.
+pub fn object<'a, I: IntoIterator<Item = (&'a str, String)>>(fields: I) -> String {
+    let fields = fields
+        .into_iter()
+        .map(|(key, value)| format!("{}:{}", string(key), value))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(string("plain"), r#""plain""#);
+        assert_eq!(
+            string("say \"hi\"\\\n\tbye\u{1}"),
+            r#""say \"hi\"\\\n\tbye\u0001""#
+        );
+    }
+
+    #[test]
+    fn nested_values() {
+        assert_eq!(
+            object([
+                ("day", "4".to_string()),
+                ("lines", array([string("#."), string(".#")])),
+            ]),
+            r##"{"day":4,"lines":["#.",".#"]}"##
+        );
+        assert_eq!(object([]), "{}");
+    }
+}
@@ -1,5 +1,27 @@
+pub mod answers;
 pub mod aoc;
+pub mod bench;
+pub mod bench_history;
+pub mod cli;
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
 pub mod day_04;
+pub mod differential;
+pub mod examples;
+pub mod fetch;
+pub mod fuzz;
+pub mod generate;
+pub mod http;
+pub mod input;
+pub mod json;
+pub mod registry;
+pub mod repl;
+pub mod rng;
+pub mod runner;
+pub mod scaffold;
+pub mod show;
+pub mod subjects;
+pub mod submit;
+pub mod table;
+pub mod watch;
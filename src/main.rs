@@ -1,9 +1,328 @@
-use aoc_2021::aoc::AOCPart;
-use aoc_2021::day_04::Part2 as Part;
-
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    let input = std::fs::read_to_string(&args[1]).unwrap();
-    let mut part = Part::new();
-    println!("{}", part.solve(&input));
+use aoc_2021::answers::{self, Answers};
+use aoc_2021::aoc::Answer;
+use aoc_2021::bench_history::{self, History};
+use aoc_2021::cli::{self, Command};
+use aoc_2021::fetch::{self, Fetched};
+use aoc_2021::input::InputSource;
+use aoc_2021::runner::{Format, Outcome};
+use aoc_2021::subjects::Subject;
+use aoc_2021::submit::{self, Verdict};
+use aoc_2021::{
+    bench, differential, fuzz, generate, input, registry, repl, runner, scaffold, show, watch,
+};
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match cli::parse_args(&args) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("error: {}\n{}", err, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Run(run) => {
+            let solver = match registry::find(run.year, run.day, run.part) {
+                Ok(solver) => solver,
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let report = runner::run_one(solver, &run.input, run.timeout);
+            match (run.format, &report.outcome) {
+                (Format::Table, Outcome::Solved(solution)) => println!("{}", solution.answer),
+                (Format::Table, Outcome::Error(err)) => eprint!("{}", err.diagnostic()),
+                (Format::Table, outcome) => eprintln!("error: {}", outcome),
+                (format, _) => print!(
+                    "{}",
+                    runner::format_reports(std::slice::from_ref(&report), format)
+                ),
+            }
+            if !matches!(report.outcome, Outcome::Solved(_)) {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::RunAll(run_all) => print!(
+            "{}",
+            runner::format_reports(
+                &runner::run_all(run_all.year, run_all.timeout),
+                run_all.format
+            )
+        ),
+        Command::Verify(verify) => {
+            let answers = match Answers::load(verify.answers.as_ref()) {
+                Ok(answers) => answers,
+                Err(err) => {
+                    eprintln!("error: {}: {}", verify.answers, err);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let verdicts: Vec<_> = runner::run_all(None, runner::DEFAULT_TIMEOUT)
+                .into_iter()
+                .map(|report| {
+                    let verdict = answers::verify(&answers, &report);
+                    println!(
+                        "{} day {:02} part {}: {}",
+                        report.year, report.day, report.part, verdict
+                    );
+                    verdict
+                })
+                .collect();
+
+            let failed = verdicts
+                .iter()
+                .filter(|verdict| verdict.is_failure())
+                .count();
+            let missing = verdicts
+                .iter()
+                .filter(|verdict| matches!(verdict, answers::Verdict::Missing { .. }))
+                .count();
+            println!(
+                "{} passed, {} failed, {} missing",
+                verdicts.len() - failed - missing,
+                failed,
+                missing
+            );
+            if failed != 0 {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Bench(args) => {
+            let solvers: Vec<_> = registry::solvers()
+                .iter()
+                .filter(|solver| args.year.is_none_or(|year| solver.year == year))
+                .filter(|solver| args.day.is_none_or(|day| solver.day == day))
+                .filter(|solver| args.part.is_none_or(|part| solver.part == part))
+                .collect();
+            if solvers.is_empty() {
+                eprintln!("error: no registered solver matches");
+                return ExitCode::FAILURE;
+            }
+            let reports = bench::bench_all(solvers, args.config);
+            print!("{}", bench::format_table(&reports));
+
+            let history_path = Path::new(&args.history);
+            let history = match History::load(history_path) {
+                Ok(history) => history,
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let mut regressed = false;
+            if args.compare {
+                let comparisons = bench_history::compare(
+                    &reports,
+                    &history,
+                    args.baseline.as_deref(),
+                    args.threshold_percent,
+                );
+                println!();
+                print!("{}", bench_history::format_comparison(&comparisons));
+                regressed = comparisons.iter().any(|comparison| comparison.regressed);
+            }
+
+            if args.save {
+                let entries = bench_history::entries_from_reports(
+                    &reports,
+                    bench_history::now(),
+                    &bench_history::current_commit(),
+                );
+                if let Err(err) = History::append(history_path, &entries) {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            }
+            if regressed {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Watch(config) => watch::watch(config),
+        Command::Show(args) => {
+            let width = args.width.unwrap_or_else(show::terminal_width);
+            let rendered = Subject::load(args.year, args.day).and_then(|subject| {
+                show::render(
+                    args.day,
+                    &subject,
+                    args.part,
+                    width,
+                    show::stdout_is_terminal(),
+                )
+            });
+            match rendered {
+                Ok(rendered) => show::page(&rendered),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Command::Fetch(args) => {
+            let path = input::input_path(args.year, args.day);
+            match fetch::fetch(args.year, args.day, &args.base_url, &path) {
+                Ok(Fetched::Cached) => println!("{}: already present", path.display()),
+                Ok(Fetched::Downloaded { bytes }) => {
+                    println!("{}: downloaded {} bytes", path.display(), bytes)
+                }
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Command::Submit(args) => {
+            let solver = match registry::find(args.year, args.day, args.part) {
+                Ok(solver) => solver,
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let answer =
+                match runner::run_one(solver, &InputSource::Default, runner::DEFAULT_TIMEOUT)
+                    .outcome
+                {
+                    Outcome::Solved(solution) => solution.answer,
+                    outcome => {
+                        eprintln!("error: {}", outcome);
+                        return ExitCode::FAILURE;
+                    }
+                };
+            if let Answer::AsciiArt(_) = answer {
+                eprintln!("error: read and submit this answer by hand:\n{}", answer);
+                return ExitCode::FAILURE;
+            }
+
+            let submitted = fetch::session_token().and_then(|session| {
+                let server = submit::Server {
+                    base_url: &args.base_url,
+                    session: &session,
+                };
+                submit::submit(
+                    args.year,
+                    args.day,
+                    args.part,
+                    &answer.to_string(),
+                    server,
+                    Path::new(submit::DEFAULT_PATH),
+                    bench_history::now(),
+                )
+            });
+            match submitted {
+                Ok(submission) => {
+                    println!(
+                        "{} day {:02} part {}: {} is {}",
+                        args.year, args.day, args.part, submission.answer, submission.verdict
+                    );
+                    if submission.wait > 0 {
+                        println!("next submission in {}s", submission.wait);
+                    }
+                    if submission.verdict != Verdict::Correct {
+                        return ExitCode::FAILURE;
+                    }
+                }
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Command::New(args) => match scaffold::scaffold(Path::new(""), args.year, args.day) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("wrote {}", path.display());
+                }
+            }
+            Err(err) => {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Repl => {
+            if let Err(err) = repl::repl() {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Gen(args) => match generate::generate(args.year, args.day, args.size, args.seed) {
+            Ok(input) => print!("{}", input),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Diff(args) => {
+            let references: Result<Vec<_>, _> = registry::solvers()
+                .iter()
+                .filter(|solver| args.year.is_none_or(|year| solver.year == year))
+                .filter(|solver| args.day.is_none_or(|day| solver.day == day))
+                .filter(|solver| args.part.is_none_or(|part| solver.part == part))
+                .map(differential::find)
+                .collect();
+            let references = match references {
+                Ok(references) if references.is_empty() => {
+                    eprintln!("error: no registered solver matches");
+                    return ExitCode::FAILURE;
+                }
+                Ok(references) => references,
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let mut mismatches = 0;
+            for reference in references {
+                let name = format!(
+                    "{} day {:02} part {}",
+                    reference.year, reference.day, reference.part
+                );
+                match reference.check(args.cases, args.size, args.seed) {
+                    Ok(None) => println!("{}: {} inputs agree", name, args.cases),
+                    Ok(Some(mismatch)) => {
+                        println!("{}: {}", name, mismatch);
+                        mismatches += 1;
+                    }
+                    Err(err) => {
+                        eprintln!("error: {}: {}", name, err);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+            if mismatches > 0 {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Fuzz(args) => {
+            let targets: Vec<_> = fuzz::targets()
+                .into_iter()
+                .filter(|target| args.year.is_none_or(|year| target.solver.year == year))
+                .filter(|target| args.day.is_none_or(|day| target.solver.day == day))
+                .collect();
+            if targets.is_empty() {
+                eprintln!("error: no registered day to fuzz");
+                return ExitCode::FAILURE;
+            }
+            for target in &targets {
+                match fuzz::fuzz(target, args.iterations, args.seed) {
+                    Ok(runs) => println!("{}: {} inputs, no panic", target.name(), runs),
+                    Err(crash) => {
+                        eprintln!("{}: panicked: {}", target.name(), crash.message);
+                        match fuzz::save_crash(Path::new(""), target, &crash) {
+                            Ok(path) => eprintln!("input saved to {}", path.display()),
+                            Err(err) => eprintln!("error: {}", err),
+                        }
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,281 @@
+use crate::aoc::{AOCPart, Answer, AocError};
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Year of the `day_XX` modules at the crate root, and of commands given no `--year`.
+pub const DEFAULT_YEAR: u32 = 2021;
+
+/// Object safe view of an `AOCPart`, so solvers of different days can be stored together.
+///
+/// The parsed input is passed around as `dyn Any`; `parsed_type` tells whether the output
+/// of one solver's `parse` can be handed to another solver's `solve`.
+pub trait DynPart {
+    fn parsed_type(&self) -> TypeId;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, AocError>;
+    fn solve(&mut self, parsed: &dyn Any) -> Result<Answer, AocError>;
+    fn run(&mut self, input: &str) -> Result<Answer, AocError>;
+}
+
+impl<P> DynPart for P
+where
+    P: AOCPart,
+    P::Parsed: 'static,
+{
+    fn parsed_type(&self) -> TypeId {
+        TypeId::of::<P::Parsed>()
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, AocError> {
+        AOCPart::parse(self, input).map(|parsed| Box::new(parsed) as Box<dyn Any>)
+    }
+
+    fn solve(&mut self, parsed: &dyn Any) -> Result<Answer, AocError> {
+        let parsed = parsed
+            .downcast_ref::<P::Parsed>()
+            .expect("parsed input given to a solver of another type");
+        AOCPart::solve(self, parsed)
+    }
+
+    fn run(&mut self, input: &str) -> Result<Answer, AocError> {
+        AOCPart::run(self, input)
+    }
+}
+
+/// Sets the year of the errors of a part, which only knows its day.
+struct InYear {
+    year: u32,
+    part: Box<dyn DynPart>,
+}
+
+impl DynPart for InYear {
+    fn parsed_type(&self) -> TypeId {
+        self.part.parsed_type()
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, AocError> {
+        self.part.parse(input).map_err(|err| err.in_year(self.year))
+    }
+
+    fn solve(&mut self, parsed: &dyn Any) -> Result<Answer, AocError> {
+        self.part
+            .solve(parsed)
+            .map_err(|err| err.in_year(self.year))
+    }
+
+    fn run(&mut self, input: &str) -> Result<Answer, AocError> {
+        self.part.run(input).map_err(|err| err.in_year(self.year))
+    }
+}
+
+pub struct Solver {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    constructor: fn() -> Box<dyn DynPart>,
+}
+
+impl Solver {
+    #[cfg(test)]
+    pub(crate) const fn new(
+        year: u32,
+        day: u32,
+        part: u32,
+        constructor: fn() -> Box<dyn DynPart>,
+    ) -> Solver {
+        Solver {
+            year,
+            day,
+            part,
+            constructor,
+        }
+    }
+
+    pub fn build(&self) -> Box<dyn DynPart> {
+        Box::new(InYear {
+            year: self.year,
+            part: (self.constructor)(),
+        })
+    }
+}
+
+impl fmt::Debug for Solver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Solver")
+            .field("year", &self.year)
+            .field("day", &self.day)
+            .field("part", &self.part)
+            .finish()
+    }
+}
+
+/// Registers both parts of each listed day module, in order.
+macro_rules! register_years {
+    ($($year:literal => { $($day:literal => $($module:ident)::+),* $(,)? }),* $(,)?) => {
+        static SOLVERS: &[Solver] = &[
+            $($(
+                Solver {
+                    year: $year,
+                    day: $day,
+                    part: 1,
+                    constructor: || Box::new(<crate::$($module)::+::Part1 as AOCPart>::new()),
+                },
+                Solver {
+                    year: $year,
+                    day: $day,
+                    part: 2,
+                    constructor: || Box::new(<crate::$($module)::+::Part2 as AOCPart>::new()),
+                },
+            )*)*
+        ];
+    };
+}
+
+register_years! {
+    2021 => {
+        1 => day_01,
+        2 => day_02,
+        3 => day_03,
+        4 => day_04,
+    },
+}
+
+/// Module of a day, `day_XX` for the default year and `year_YYYY::day_XX` otherwise.
+pub fn module_name(year: u32, day: u32) -> String {
+    match year {
+        DEFAULT_YEAR => format!("day_{:02}", day),
+        year => format!("year_{}::day_{:02}", year, day),
+    }
+}
+
+pub fn source_path(year: u32, day: u32) -> PathBuf {
+    PathBuf::from(format!(
+        "src/{}.rs",
+        module_name(year, day).replace("::", "/")
+    ))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    UnknownSolver { year: u32, day: u32, part: u32 },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::UnknownSolver { year, day, part } => {
+                write!(f, "{} day {} part {} is not implemented", year, day, part)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+pub fn solvers() -> &'static [Solver] {
+    SOLVERS
+}
+
+pub fn find(year: u32, day: u32, part: u32) -> Result<&'static Solver, RegistryError> {
+    SOLVERS
+        .iter()
+        .find(|solver| solver.year == year && solver.day == day && solver.part == part)
+        .ok_or(RegistryError::UnknownSolver { year, day, part })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn solvers_are_ordered_and_unique() {
+        let keys: Vec<(u32, u32, u32)> = solvers()
+            .iter()
+            .map(|solver| (solver.year, solver.day, solver.part))
+            .collect();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", keys);
+        assert_eq!(
+            keys[..8],
+            [
+                (2021, 1, 1),
+                (2021, 1, 2),
+                (2021, 2, 1),
+                (2021, 2, 2),
+                (2021, 3, 1),
+                (2021, 3, 2),
+                (2021, 4, 1),
+                (2021, 4, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn find_registered() {
+        let input = "199\n200\n208\n210\n200";
+        assert_eq!(find(2021, 1, 1).unwrap().build().run(input), Ok(3.into()));
+        assert_eq!(find(2021, 1, 2).unwrap().build().run(input), Ok(1.into()));
+    }
+
+    #[test]
+    fn parsed_input_is_shared_between_parts() {
+        let part1 = find(2021, 4, 1).unwrap().build();
+        let mut part2 = find(2021, 4, 2).unwrap().build();
+        assert_eq!(part1.parsed_type(), part2.parsed_type());
+        assert_ne!(
+            part1.parsed_type(),
+            find(2021, 3, 1).unwrap().build().parsed_type()
+        );
+
+        let parsed = part1.parse("5,1,3\n\n1 2\n3 4").unwrap();
+        assert_eq!(part2.solve(parsed.as_ref()), Ok(18.into()));
+    }
+
+    #[test]
+    fn errors_are_of_the_solver_year() {
+        static SOLVER: Solver = Solver::new(2015, 1, 1, || {
+            Box::new(<crate::day_01::Part1 as AOCPart>::new())
+        });
+        let mut part = SOLVER.build();
+        assert_eq!(part.run("x").unwrap_err().year, 2015);
+        assert_eq!(part.parse("x").unwrap_err().year, 2015);
+        assert_eq!(
+            find(2021, 1, 1).unwrap().build().run("x").unwrap_err().year,
+            2021
+        );
+    }
+
+    #[test]
+    fn find_unknown() {
+        assert_eq!(
+            find(2021, 26, 1).unwrap_err(),
+            RegistryError::UnknownSolver {
+                year: 2021,
+                day: 26,
+                part: 1
+            }
+        );
+        assert_eq!(
+            find(2021, 1, 3).unwrap_err().to_string(),
+            "2021 day 1 part 3 is not implemented"
+        );
+        assert_eq!(
+            find(2020, 1, 1).unwrap_err(),
+            RegistryError::UnknownSolver {
+                year: 2020,
+                day: 1,
+                part: 1
+            }
+        );
+    }
+
+    #[test]
+    fn module_layout() {
+        assert_eq!(module_name(2021, 4), "day_04");
+        assert_eq!(source_path(2021, 4), PathBuf::from("src/day_04.rs"));
+        assert_eq!(module_name(2022, 12), "year_2022::day_12");
+        assert_eq!(
+            source_path(2022, 12),
+            PathBuf::from("src/year_2022/day_12.rs")
+        );
+    }
+}
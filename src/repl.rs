@@ -0,0 +1,841 @@
+use crate::input::InputSource;
+use crate::registry::{self, DEFAULT_YEAR};
+use crate::runner;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{BufRead, Write};
+
+/// Items of a list shown before eliding the rest.
+const SHOWN_ITEMS: usize = 10;
+/// Characters of a text or native value shown before eliding the rest.
+const SHOWN_CHARS: usize = 120;
+
+const HELP: &str = "commands:
+  day <day> [<year>]          switch day, variables are kept
+  load [<path> | example <n>] read an input into 'input'
+  solve <part>                run a registered part on 'input'
+  helpers                     list the helpers of the day
+  vars                        list the variables
+  show <name>                 print a variable in full
+  quit
+expressions:
+  [<name> =] <helper> <argument>...   call a helper, mutated variables are updated
+  [<name> =] <value>                  integers, \"text\", [lists] or variable names
+the result of the last expression is kept in '_'";
+
+/// A value held by the interpreter.
+#[derive(Debug)]
+pub enum Value {
+    Int(i64),
+    Text(String),
+    List(Vec<Value>),
+    /// A value of a day's own type, shown through its `Debug` output.
+    Native(Box<dyn NativeValue>),
+}
+
+pub trait NativeValue: fmt::Debug {
+    fn type_name(&self) -> &'static str;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn clone_box(&self) -> Box<dyn NativeValue>;
+}
+
+impl<T: Any + fmt::Debug + Clone> NativeValue for T {
+    fn type_name(&self) -> &'static str {
+        let name = std::any::type_name::<T>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn NativeValue> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Value {
+    fn clone(&self) -> Self {
+        match self {
+            Value::Int(value) => Value::Int(*value),
+            Value::Text(text) => Value::Text(text.clone()),
+            Value::List(items) => Value::List(items.clone()),
+            Value::Native(native) => Value::Native(native.as_ref().clone_box()),
+        }
+    }
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Text(_) => "text",
+            Value::List(_) => "list",
+            Value::Native(native) => native.as_ref().type_name(),
+        }
+    }
+
+    /// Everything, natives pretty printed.
+    pub fn full(&self) -> String {
+        match self {
+            Value::Native(native) => format!("{:#?}", native),
+            Value::List(items) => format!(
+                "[{}]",
+                items.iter().map(Value::full).collect::<Vec<_>>().join(", ")
+            ),
+            value => value.to_string(),
+        }
+    }
+}
+
+fn elide(text: String) -> String {
+    match text.char_indices().nth(SHOWN_CHARS) {
+        Some((index, _)) => format!("{}... ({} chars)", &text[..index], text.chars().count()),
+        None => text,
+    }
+}
+
+/// Short form, long lists and texts are elided.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Text(text) => write!(f, "{}", elide(format!("{:?}", text))),
+            Value::List(items) => {
+                let shown: Vec<String> = items
+                    .iter()
+                    .take(SHOWN_ITEMS)
+                    .map(|item| item.to_string())
+                    .collect();
+                match items.len().checked_sub(SHOWN_ITEMS) {
+                    Some(more) if more > 0 => {
+                        write!(f, "[{}, ... {} more]", shown.join(", "), more)
+                    }
+                    _ => write!(f, "[{}]", shown.join(", ")),
+                }
+            }
+            Value::Native(native) => write!(f, "{}", elide(format!("{:?}", native))),
+        }
+    }
+}
+
+pub trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, String>;
+}
+
+fn mismatch(expected: &str, value: &Value) -> String {
+    format!("expected {}, got {}", expected, value.type_name())
+}
+
+macro_rules! int_values {
+    ($($int:ty),*) => {
+        $(
+            impl IntoValue for $int {
+                fn into_value(self) -> Value {
+                    Value::Int(self as i64)
+                }
+            }
+
+            impl FromValue for $int {
+                fn from_value(value: &Value) -> Result<Self, String> {
+                    match value {
+                        Value::Int(int) => <$int>::try_from(*int)
+                            .map_err(|_| format!("{} is out of range for {}", int, stringify!($int))),
+                        value => Err(mismatch("int", value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+int_values!(i32, i64, u32, u64, usize);
+
+/// Beyond the range of an int, shown as text rather than wrapped.
+impl IntoValue for u128 {
+    fn into_value(self) -> Value {
+        i64::try_from(self).map_or_else(|_| Value::Text(self.to_string()), Value::Int)
+    }
+}
+
+impl IntoValue for String {
+    fn into_value(self) -> Value {
+        Value::Text(self)
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Text(text) => Ok(text.clone()),
+            value => Err(mismatch("text", value)),
+        }
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Value {
+        Value::List(self.into_iter().map(IntoValue::into_value).collect())
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::List(items) => items.iter().map(T::from_value).collect(),
+            value => Err(mismatch("list", value)),
+        }
+    }
+}
+
+impl<A: IntoValue, B: IntoValue> IntoValue for (A, B) {
+    fn into_value(self) -> Value {
+        Value::List(vec![self.0.into_value(), self.1.into_value()])
+    }
+}
+
+impl<A: IntoValue, B: IntoValue, C: IntoValue> IntoValue for (A, B, C) {
+    fn into_value(self) -> Value {
+        Value::List(vec![
+            self.0.into_value(),
+            self.1.into_value(),
+            self.2.into_value(),
+        ])
+    }
+}
+
+/// Lets the interpreter hold values of a day's own types.
+macro_rules! native_values {
+    ($($native:ty),* $(,)?) => {
+        $(
+            impl $crate::repl::IntoValue for $native {
+                fn into_value(self) -> $crate::repl::Value {
+                    $crate::repl::Value::Native(Box::new(self))
+                }
+            }
+
+            impl $crate::repl::FromValue for $native {
+                fn from_value(value: &$crate::repl::Value) -> Result<Self, String> {
+                    match value {
+                        $crate::repl::Value::Native(native) => native.as_ref()
+                            .as_any()
+                            .downcast_ref::<$native>()
+                            .cloned(),
+                        _ => None,
+                    }
+                    .ok_or_else(|| {
+                        format!("expected {}, got {}", stringify!($native), value.type_name())
+                    })
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use native_values;
+
+/// Arguments of a helper call, helpers may mutate them in place.
+pub struct Args(Vec<Value>);
+
+impl Args {
+    pub fn get<T: FromValue>(&self, index: usize) -> Result<T, String> {
+        T::from_value(&self.0[index]).map_err(|err| format!("argument {}: {}", index + 1, err))
+    }
+
+    pub fn native_mut<T: Any>(&mut self, index: usize) -> Result<&mut T, String> {
+        let value = &mut self.0[index];
+        let type_name = value.type_name();
+        match value {
+            Value::Native(native) => native.as_any_mut().downcast_mut::<T>(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("argument {}: unexpected {}", index + 1, type_name))
+    }
+}
+
+/// A function of a day made callable from the interpreter.
+pub struct Helper {
+    pub name: &'static str,
+    /// Argument names, their count is checked before calling.
+    pub params: &'static str,
+    pub call: fn(&mut Args) -> Result<Value, String>,
+}
+
+impl Helper {
+    fn usage(&self) -> String {
+        format!("{} {}", self.name, self.params)
+            .trim_end()
+            .to_string()
+    }
+}
+
+/// Helpers of the days that provide some, in their own module.
+static DAY_HELPERS: &[(u32, u32, &[Helper])] = &[
+    (2021, 1, crate::day_01::REPL_HELPERS),
+    (2021, 2, crate::day_02::REPL_HELPERS),
+    (2021, 3, crate::day_03::REPL_HELPERS),
+    (2021, 4, crate::day_04::REPL_HELPERS),
+];
+
+/// Functions of a day callable from the REPL, none for a day not listed in `DAY_HELPERS`.
+pub fn helpers(year: u32, day: u32) -> &'static [Helper] {
+    #[cfg(test)]
+    if (year, day) == test::PANICKING_DAY {
+        return test::PANICKING_HELPERS;
+    }
+    DAY_HELPERS
+        .iter()
+        .find(|(helper_year, helper_day, _)| (*helper_year, *helper_day) == (year, day))
+        .map_or(&[], |(_, _, helpers)| helpers)
+}
+
+/// Available whatever the day.
+const BUILTINS: &[Helper] = &[
+    Helper {
+        name: "len",
+        params: "value",
+        call: |args| match &args.0[0] {
+            Value::List(items) => Ok(items.len().into_value()),
+            Value::Text(text) => Ok(text.chars().count().into_value()),
+            value => Err(mismatch("list or text", value)),
+        },
+    },
+    Helper {
+        name: "get",
+        params: "list index",
+        call: |args| {
+            let items: Vec<Value> = match &args.0[0] {
+                Value::List(items) => items.clone(),
+                value => return Err(mismatch("list", value)),
+            };
+            let index: usize = args.get(1)?;
+            items
+                .into_iter()
+                .nth(index)
+                .ok_or_else(|| format!("index {} is out of the list", index))
+        },
+    },
+    Helper {
+        name: "lines",
+        params: "text",
+        call: |args| {
+            let text: String = args.get(0)?;
+            Ok(text
+                .lines()
+                .map(String::from)
+                .collect::<Vec<_>>()
+                .into_value())
+        },
+    },
+];
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Open,
+    Close,
+    Comma,
+    Assign,
+}
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&char) = chars.peek() {
+        match char {
+            '#' => break,
+            char if char.is_whitespace() => {
+                chars.next();
+            }
+            '[' | ']' | ',' | '=' => {
+                chars.next();
+                tokens.push(match char {
+                    '[' => Token::Open,
+                    ']' => Token::Close,
+                    ',' => Token::Comma,
+                    _ => Token::Assign,
+                });
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some(escaped) => text.push(escaped),
+                            None => return Err("unterminated text".to_string()),
+                        },
+                        Some(char) => text.push(char),
+                        None => return Err("unterminated text".to_string()),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            char if is_word(char) => {
+                let mut word = String::new();
+                while let Some(&char) = chars.peek().filter(|&&char| is_word(char)) {
+                    word.push(char);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+            char => return Err(format!("unexpected '{}'", char)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_word(char: char) -> bool {
+    char.is_alphanumeric() || matches!(char, '_' | ':' | '-')
+}
+
+/// An argument as written, variables are resolved when calling.
+#[derive(Debug, PartialEq, Eq)]
+enum Atom {
+    Int(i64),
+    Text(String),
+    Var(String),
+    List(Vec<Atom>),
+}
+
+fn parse_atoms(tokens: &[Token]) -> Result<Vec<Atom>, String> {
+    let mut atoms = Vec::new();
+    let mut stack: Vec<Vec<Atom>> = Vec::new();
+    for token in tokens {
+        let atom = match token {
+            Token::Open => {
+                stack.push(Vec::new());
+                continue;
+            }
+            Token::Close => Atom::List(stack.pop().ok_or("unexpected ']'")?),
+            Token::Comma if !stack.is_empty() => continue,
+            Token::Comma => return Err("unexpected ','".to_string()),
+            Token::Assign => return Err("unexpected '='".to_string()),
+            Token::Text(text) => Atom::Text(text.clone()),
+            Token::Word(word) => match word.parse() {
+                Ok(int) => Atom::Int(int),
+                Err(_) if word.starts_with(|char: char| char.is_ascii_digit() || char == '-') => {
+                    return Err(format!("invalid number '{}'", word))
+                }
+                Err(_) => Atom::Var(word.clone()),
+            },
+        };
+        match stack.last_mut() {
+            Some(list) => list.push(atom),
+            None => atoms.push(atom),
+        }
+    }
+    if !stack.is_empty() {
+        return Err("missing ']'".to_string());
+    }
+    Ok(atoms)
+}
+
+pub enum Reply {
+    Output(String),
+    Quit,
+}
+
+pub struct Session {
+    pub year: u32,
+    pub day: u32,
+    vars: BTreeMap<String, Value>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session {
+            year: DEFAULT_YEAR,
+            day: 1,
+            vars: BTreeMap::new(),
+        }
+    }
+}
+
+impl Session {
+    pub fn prompt(&self) -> String {
+        format!("{} day {}> ", self.year, self.day)
+    }
+
+    pub fn var(&self, name: &str) -> Option<&Value> {
+        self.vars.get(name)
+    }
+
+    pub fn execute(&mut self, line: &str) -> Result<Reply, String> {
+        let tokens = tokenize(line)?;
+        let (target, tokens) = match &tokens[..] {
+            [Token::Word(name), Token::Assign, rest @ ..] => (Some(name.clone()), rest),
+            tokens => (None, tokens),
+        };
+        let output = match (&target, tokens) {
+            (_, []) if target.is_none() => String::new(),
+            (None, [Token::Word(command), rest @ ..]) if is_command(command) => {
+                return self.command(command, &parse_atoms(rest)?)
+            }
+            (_, [Token::Word(name), rest @ ..]) if self.helper(name).is_some() => {
+                let value = self.call(name, parse_atoms(rest)?)?;
+                self.store(target, value)
+            }
+            (_, tokens) => match &parse_atoms(tokens)?[..] {
+                [atom] => {
+                    let value = self.resolve(atom)?;
+                    self.store(target, value)
+                }
+                _ => return Err(format!("'{}' is not a helper of the day", line.trim())),
+            },
+        };
+        Ok(Reply::Output(output))
+    }
+
+    fn store(&mut self, target: Option<String>, value: Value) -> String {
+        let output = match &target {
+            Some(name) => format!("{}: {} = {}", name, value.type_name(), value),
+            None => value.to_string(),
+        };
+        self.vars
+            .insert(target.unwrap_or_else(|| "_".to_string()), value);
+        output
+    }
+
+    fn helper(&self, name: &str) -> Option<&'static Helper> {
+        helpers(self.year, self.day)
+            .iter()
+            .chain(BUILTINS)
+            .find(|helper| helper.name == name)
+    }
+
+    fn resolve(&self, atom: &Atom) -> Result<Value, String> {
+        match atom {
+            Atom::Int(int) => Ok(Value::Int(*int)),
+            Atom::Text(text) => Ok(Value::Text(text.clone())),
+            Atom::Var(name) => self
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("unknown variable '{}'", name)),
+            Atom::List(atoms) => atoms
+                .iter()
+                .map(|atom| self.resolve(atom))
+                .collect::<Result<_, _>>()
+                .map(Value::List),
+        }
+    }
+
+    /// Calls the helper, then writes the arguments given as variables back.
+    fn call(&mut self, name: &str, atoms: Vec<Atom>) -> Result<Value, String> {
+        let helper = self.helper(name).expect("helper looked up before calling");
+        if atoms.len() != helper.params.split_whitespace().count() {
+            return Err(format!("usage: {}", helper.usage()));
+        }
+        let mut args = Args(
+            atoms
+                .iter()
+                .map(|atom| self.resolve(atom))
+                .collect::<Result<_, _>>()?,
+        );
+        let value = runner::catch_panic(|| (helper.call)(&mut args))
+            .map_err(|message| format!("{} panicked: {}", name, message))??;
+        for (atom, arg) in atoms.iter().zip(args.0) {
+            if let Atom::Var(var) = atom {
+                self.vars.insert(var.clone(), arg);
+            }
+        }
+        Ok(value)
+    }
+
+    fn command(&mut self, command: &str, atoms: &[Atom]) -> Result<Reply, String> {
+        let output = match (command, atoms) {
+            ("quit" | "exit", []) => return Ok(Reply::Quit),
+            ("help", []) => HELP.to_string(),
+            ("day", [Atom::Int(day)]) => self.switch_day(self.year as i64, *day)?,
+            ("day", [Atom::Int(day), Atom::Int(year)]) => self.switch_day(*year, *day)?,
+            ("load", rest) => {
+                let source = match rest {
+                    [] => InputSource::Default,
+                    [Atom::Var(example), Atom::Int(index)] if example == "example" => {
+                        InputSource::Example(*index as usize)
+                    }
+                    [Atom::Var(path)] | [Atom::Text(path)] => InputSource::from_arg(path),
+                    _ => return Err("usage: load [<path> | example <n>]".to_string()),
+                };
+                let input = source.read(self.year, self.day)?;
+                let output = format!(
+                    "input: {} lines from {}",
+                    input.lines().count(),
+                    source.path(self.year, self.day).display()
+                );
+                self.vars.insert("input".to_string(), Value::Text(input));
+                output
+            }
+            ("solve", [Atom::Int(part)]) => {
+                let solver = registry::find(self.year, self.day, *part as u32)
+                    .map_err(|err| err.to_string())?;
+                let input: String = self
+                    .vars
+                    .get("input")
+                    .ok_or_else(|| "no input, use 'load' first".to_string())
+                    .and_then(String::from_value)?;
+                let solution = runner::catch_panic(|| runner::run_solver(solver, &input))
+                    .map_err(|message| format!("panicked: {}", message))?
+                    .map_err(|err| err.to_string())?;
+                format!(
+                    "{} (parse {}, solve {})",
+                    solution.answer,
+                    crate::table::format_duration(solution.parse_time.unwrap_or_default()),
+                    crate::table::format_duration(solution.solve_time)
+                )
+            }
+            ("helpers", []) => helpers(self.year, self.day)
+                .iter()
+                .chain(BUILTINS)
+                .map(Helper::usage)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ("vars", []) => self
+                .vars
+                .iter()
+                .map(|(name, value)| format!("{}: {} = {}", name, value.type_name(), value))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ("show", [Atom::Var(name)]) => self
+                .vars
+                .get(name)
+                .map(Value::full)
+                .ok_or_else(|| format!("unknown variable '{}'", name))?,
+            _ => return Err(format!("invalid '{}' command, see 'help'", command)),
+        };
+        Ok(Reply::Output(output))
+    }
+
+    fn switch_day(&mut self, year: i64, day: i64) -> Result<String, String> {
+        let (year, day) = (year as u32, day as u32);
+        registry::find(year, day, 1)
+            .map_err(|_| format!("{} day {} is not registered", year, day))?;
+        self.year = year;
+        self.day = day;
+        Ok(format!(
+            "{} day {}, {} helpers",
+            year,
+            day,
+            helpers(year, day).len()
+        ))
+    }
+}
+
+fn is_command(word: &str) -> bool {
+    matches!(
+        word,
+        "quit" | "exit" | "help" | "day" | "load" | "solve" | "helpers" | "vars" | "show"
+    )
+}
+
+/// Reads commands from stdin until `quit` or the end of input.
+pub fn repl() -> Result<(), String> {
+    let mut session = Session::default();
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    println!("aoc_2021 repl, 'help' lists the commands");
+    loop {
+        print!("{}", session.prompt());
+        std::io::stdout().flush().map_err(|err| err.to_string())?;
+        let line = match lines.next() {
+            Some(line) => line.map_err(|err| err.to_string())?,
+            None => return Ok(()),
+        };
+        match session.execute(&line) {
+            Ok(Reply::Quit) => return Ok(()),
+            Ok(Reply::Output(output)) if output.is_empty() => (),
+            Ok(Reply::Output(output)) => println!("{}", output),
+            Err(err) => println!("error: {}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A day of no year, whose helper panics.
+    pub(super) const PANICKING_DAY: (u32, u32) = (0, 1);
+
+    pub(super) const PANICKING_HELPERS: &[Helper] = &[Helper {
+        name: "give_up",
+        params: "value",
+        call: |_| panic!("gave up"),
+    }];
+
+    fn run(session: &mut Session, line: &str) -> String {
+        match session.execute(line) {
+            Ok(Reply::Output(output)) => output,
+            Ok(Reply::Quit) => "quit".to_string(),
+            Err(err) => format!("error: {}", err),
+        }
+    }
+
+    #[test]
+    fn tokenize_lines() {
+        assert_eq!(
+            tokenize(r#"m = f [1, -2] "a\"b\n" # comment"#),
+            Ok(vec![
+                Token::Word("m".to_string()),
+                Token::Assign,
+                Token::Word("f".to_string()),
+                Token::Open,
+                Token::Word("1".to_string()),
+                Token::Comma,
+                Token::Word("-2".to_string()),
+                Token::Close,
+                Token::Text("a\"b\n".to_string()),
+            ])
+        );
+        assert_eq!(tokenize("\"open"), Err("unterminated text".to_string()));
+        assert_eq!(tokenize("a; b"), Err("unexpected ';'".to_string()));
+    }
+
+    #[test]
+    fn parse_atom_lists() {
+        assert_eq!(
+            parse_atoms(&tokenize("[1 [x]] \"t\"").unwrap()),
+            Ok(vec![
+                Atom::List(vec![
+                    Atom::Int(1),
+                    Atom::List(vec![Atom::Var("x".to_string())])
+                ]),
+                Atom::Text("t".to_string()),
+            ])
+        );
+        assert_eq!(
+            parse_atoms(&tokenize("[1").unwrap()),
+            Err("missing ']'".to_string())
+        );
+        assert_eq!(
+            parse_atoms(&tokenize("12a").unwrap()),
+            Err("invalid number '12a'".to_string())
+        );
+    }
+
+    #[test]
+    fn value_display() {
+        assert_eq!(
+            (1..=12).collect::<Vec<u32>>().into_value().to_string(),
+            "[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, ... 2 more]"
+        );
+        assert_eq!(vec![(1, 2)].into_value().to_string(), "[[1, 2]]");
+        assert_eq!("a\nb".to_string().into_value().to_string(), r#""a\nb""#);
+        assert_eq!(
+            Vec::<u32>::from_value(&Value::Int(3)),
+            Err("expected list, got int".to_string())
+        );
+        assert_eq!(
+            u32::from_value(&Value::Int(-3)),
+            Err("-3 is out of range for u32".to_string())
+        );
+    }
+
+    #[test]
+    fn helpers_are_optional() {
+        assert_eq!(helpers(2021, 3).len(), 5);
+        assert!(helpers(2021, 25).is_empty());
+        assert!(helpers(2020, 3).is_empty());
+    }
+
+    #[test]
+    fn session_calls_day_helpers() {
+        let mut session = Session::default();
+        assert_eq!(
+            run(
+                &mut session,
+                r#"input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263""#
+            ),
+            r#"input: text = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263""#
+        );
+        assert_eq!(
+            run(&mut session, "m = retrieve_depth_measurements input"),
+            "m: list = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263]"
+        );
+        assert_eq!(run(&mut session, "count_depth_increases m"), "7");
+        assert_eq!(run(&mut session, "count_depth_increases [1 2 3]"), "2");
+        assert_eq!(run(&mut session, "get m 1"), "200");
+        assert_eq!(
+            run(&mut session, "get _ 0"),
+            "error: expected list, got int"
+        );
+        assert!(run(&mut session, "solve 1").starts_with("7 (parse "));
+        assert_eq!(
+            run(&mut session, "count_depth_increases"),
+            "error: usage: count_depth_increases measurements"
+        );
+        assert_eq!(
+            run(&mut session, "calc_gamma m 5"),
+            "error: 'calc_gamma m 5' is not a helper of the day"
+        );
+
+        assert_eq!(run(&mut session, "day 3"), "2021 day 3, 5 helpers");
+        assert_eq!(
+            run(
+                &mut session,
+                "calc_gamma [4 30 22 23 21 15 7 28 16 25 2 10] 5"
+            ),
+            "9"
+        );
+        assert_eq!(
+            run(&mut session, "day 30"),
+            "error: 2021 day 30 is not registered"
+        );
+        assert_eq!(run(&mut session, "quit"), "quit");
+    }
+
+    #[test]
+    fn session_updates_mutated_variables() {
+        let mut session = Session::default();
+        run(&mut session, "day 4");
+        assert_eq!(
+            run(&mut session, "load example 1"),
+            "input: 19 lines from subjects/2021/day_04.txt#example-1"
+        );
+        assert!(run(&mut session, "solve 2").starts_with("1924 (parse "));
+        run(
+            &mut session,
+            r#"game = retrieve_bingo_game "1,2\n\n1 2\n3 4""#,
+        );
+        assert_eq!(run(&mut session, "boards = BingoGame::boards game"), "boards: list = [BingoBoard { bingo_status: Bango, bingo_lines: [[Unmarked(1), Unmarked(2)], [Unmarked(3), Unmarked(4)]] }]");
+        run(&mut session, "board = get boards 0");
+        assert_eq!(
+            run(&mut session, "BingoBoard::incoming_number board 1"),
+            "Bango"
+        );
+        assert_eq!(
+            run(&mut session, "BingoBoard::incoming_number board 2"),
+            "Bingo(7)"
+        );
+        assert_eq!(run(&mut session, "BingoBoard::calc_bingo_value board"), "7");
+        assert_eq!(
+            run(&mut session, "BingoBoard::incoming_number game 1"),
+            "error: argument 1: unexpected BingoGame"
+        );
+    }
+
+    #[test]
+    fn session_survives_panics() {
+        let mut session = Session::default();
+        (session.year, session.day) = PANICKING_DAY;
+        assert_eq!(
+            run(&mut session, "give_up 1"),
+            "error: give_up panicked: gave up"
+        );
+        assert_eq!(run(&mut session, "len [1 2]"), "2");
+    }
+}
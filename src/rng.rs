@@ -0,0 +1,118 @@
+use std::ops::Range;
+
+/// SplitMix64, small and good enough to generate test data, not for anything secret.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        let span = range.end - range.start;
+        // rejecting the top of the u64 range removes the modulo bias
+        let zone = u64::MAX - (u64::MAX - span + 1) % span;
+        loop {
+            let value = self.next_u64();
+            if value <= zone {
+                return range.start + value % span;
+            }
+        }
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        self.range(0..bound as u64) as usize
+    }
+
+    /// True with probability `numerator / denominator`.
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.range(0..denominator) < numerator
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+/// Runs `property` against `cases` generators seeded 0, 1, ..., naming the seed of the
+/// first failing case. A single seed can be replayed with `AOC_PROPERTY_SEED`.
+#[cfg(test)]
+pub fn check(cases: u64, mut property: impl FnMut(&mut Rng)) {
+    let seeds = match std::env::var("AOC_PROPERTY_SEED") {
+        Ok(seed) => {
+            let seed = seed.parse().expect("AOC_PROPERTY_SEED is not a number");
+            seed..seed + 1
+        }
+        Err(_) => 0..cases,
+    };
+    for seed in seeds {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            property(&mut Rng::new(seed))
+        }));
+        if let Err(payload) = result {
+            eprintln!("property failed with seed {}", seed);
+            std::panic::resume_unwind(payload);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deterministic_sequences() {
+        let mut first = Rng::new(7);
+        let mut second = Rng::new(7);
+        let values: Vec<u64> = (0..4).map(|_| first.next_u64()).collect();
+        assert_eq!(
+            values,
+            (0..4).map(|_| second.next_u64()).collect::<Vec<_>>()
+        );
+        assert_ne!(
+            values,
+            (0..4).map(|_| Rng::new(8).next_u64()).collect::<Vec<_>>()
+        );
+        // reference output of SplitMix64 seeded with 0
+        assert_eq!(Rng::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn ranges_stay_in_bounds() {
+        check(20, |rng| {
+            let start = rng.range(0..1000);
+            let end = start + 1 + rng.range(0..10);
+            for _ in 0..100 {
+                assert!((start..end).contains(&rng.range(start..end)));
+            }
+            assert_eq!(rng.range(5..6), 5);
+            assert!(rng.range(0..u64::MAX) < u64::MAX);
+        });
+    }
+
+    #[test]
+    fn shuffle_permutes() {
+        check(20, |rng| {
+            let mut items: Vec<usize> = (0..rng.below(20)).collect();
+            rng.shuffle(&mut items);
+            let mut sorted = items.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..items.len()).collect::<Vec<_>>());
+        });
+    }
+}
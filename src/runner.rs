@@ -0,0 +1,678 @@
+use crate::aoc::{Answer, AocError};
+use crate::input::{input_path, InputSource};
+use crate::json;
+use crate::registry::{self, Solver};
+use crate::table::{self, format_duration};
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub struct RunReport {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub input_path: PathBuf,
+    /// `None` when the input could not be read.
+    pub input_hash: Option<u64>,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Solved(Solution),
+    Error(AocError),
+    Failed(String),
+    /// The solver was still running after the timeout, it is left behind.
+    Timeout(Duration),
+    /// The solver panicked with this message.
+    Panic(String),
+}
+
+#[derive(Debug)]
+pub struct Solution {
+    pub answer: Answer,
+    /// `None` when the input parsed for the other part of the same day was reused.
+    pub parse_time: Option<Duration>,
+    pub solve_time: Duration,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format '{}'", format)),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Solved(solution) => write!(f, "{}", solution.answer),
+            Outcome::Error(err) => write!(f, "{}", err),
+            Outcome::Failed(message) => write!(f, "{}", message),
+            Outcome::Timeout(timeout) => write!(f, "TIMEOUT after {}", format_duration(*timeout)),
+            Outcome::Panic(message) => write!(f, "PANIC: {}", message),
+        }
+    }
+}
+
+impl RunReport {
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![
+            ("year", self.year.to_string()),
+            ("day", self.day.to_string()),
+            ("part", self.part.to_string()),
+        ];
+        match &self.outcome {
+            Outcome::Solved(solution) => {
+                fields.push(("answer", solution.answer.to_json()));
+                fields.push((
+                    "parse_ns",
+                    solution
+                        .parse_time
+                        .map_or_else(|| "null".to_string(), |time| time.as_nanos().to_string()),
+                ));
+                fields.push(("solve_ns", solution.solve_time.as_nanos().to_string()));
+            }
+            outcome => fields.push(("error", json::string(&outcome.to_string()))),
+        }
+        fields.push((
+            "input_path",
+            json::string(&self.input_path.to_string_lossy()),
+        ));
+        fields.push((
+            "input_hash",
+            self.input_hash.map_or_else(
+                || "null".to_string(),
+                |hash| json::string(&format_hash(hash)),
+            ),
+        ));
+        json::object(fields)
+    }
+}
+
+/// 64 bits FNV-1a, stable across platforms and Rust versions unlike `DefaultHasher`.
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+pub fn format_hash(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+pub fn run_solver(solver: &Solver, input: &str) -> Result<Solution, AocError> {
+    let mut part = solver.build();
+    let (parsed, parse_time) = timed(|| part.parse(input));
+    let (answer, solve_time) = timed(|| part.solve(parsed?.as_ref()));
+    Ok(Solution {
+        answer: answer?,
+        parse_time: Some(parse_time),
+        solve_time,
+    })
+}
+
+pub fn run_one(solver: &'static Solver, source: &InputSource, timeout: Duration) -> RunReport {
+    let (input_hash, outcome) = match source.read(solver.year, solver.day) {
+        Ok(input) => {
+            let mut outcomes = run_isolated(std::slice::from_ref(solver), &input, timeout);
+            (Some(input_hash(&input)), outcomes.remove(0))
+        }
+        Err(message) => (None, Outcome::Failed(message)),
+    };
+    RunReport {
+        year: solver.year,
+        day: solver.day,
+        part: solver.part,
+        input_path: source.path(solver.year, solver.day),
+        input_hash,
+        outcome,
+    }
+}
+
+/// Every registered solver, or only those of `year`.
+pub fn run_all(year: Option<u32>, timeout: Duration) -> Vec<RunReport> {
+    registry::solvers()
+        .chunk_by(|first, second| (first.year, first.day) == (second.year, second.day))
+        .filter(|day_solvers| year.is_none_or(|year| day_solvers[0].year == year))
+        .flat_map(|day_solvers| {
+            let (year, day) = (day_solvers[0].year, day_solvers[0].day);
+            let path = input_path(year, day);
+            let input = InputSource::Default.read(year, day);
+            let outcomes = match &input {
+                Ok(input) => run_isolated(day_solvers, input, timeout),
+                Err(message) => day_solvers
+                    .iter()
+                    .map(|_| Outcome::Failed(message.clone()))
+                    .collect(),
+            };
+
+            day_solvers
+                .iter()
+                .zip(outcomes)
+                .map(|(solver, outcome)| RunReport {
+                    year,
+                    day,
+                    part: solver.part,
+                    input_path: path.clone(),
+                    input_hash: input.as_deref().ok().map(input_hash),
+                    outcome,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Runs the solvers of a day in order on a worker thread, sharing the parsed input, and
+/// gives each one `timeout` to answer.
+///
+/// A panic only fails its own solver. After a timeout the worker is abandoned and the
+/// remaining solvers go on with a new one.
+pub fn run_isolated(solvers: &'static [Solver], input: &str, timeout: Duration) -> Vec<Outcome> {
+    // covers the whole run, the worker thread catching each panic on its own
+    let _quiet = QuietPanics::new();
+    let input: Arc<str> = Arc::from(input);
+    let mut outcomes = Vec::with_capacity(solvers.len());
+    while outcomes.len() < solvers.len() {
+        let pending = &solvers[outcomes.len()..];
+        let (sender, receiver) = mpsc::channel();
+        let worker_input = Arc::clone(&input);
+        thread::spawn(move || {
+            let mut shared_parsed = None;
+            for solver in pending {
+                let result = catch_panic(|| run_shared(solver, &worker_input, &mut shared_parsed));
+                if sender.send(result).is_err() {
+                    return;
+                }
+            }
+        });
+
+        for _ in pending {
+            match receiver.recv_timeout(timeout) {
+                Ok(Ok(result)) => outcomes.push(solved(result)),
+                Ok(Err(message)) => outcomes.push(Outcome::Panic(message)),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    outcomes.push(Outcome::Timeout(timeout));
+                    break;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    outcomes.push(Outcome::Panic("worker thread died".to_string()));
+                    break;
+                }
+            }
+        }
+    }
+    outcomes
+}
+
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo) + Sync + Send>;
+
+/// The panic hook replaced while panics are caught, and how many callers are catching them.
+static QUIET: Mutex<(usize, Option<Arc<PanicHook>>)> = Mutex::new((0, None));
+
+thread_local! {
+    /// Whether a panic on this thread is caught by `catch_panic`.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// While alive, panics caught by `catch_panic` are not printed by the panic hook. Panics of
+/// other threads still go to the replaced hook, which is restored by the last guard.
+struct QuietPanics;
+
+impl QuietPanics {
+    fn new() -> QuietPanics {
+        let mut quiet = QUIET.lock().unwrap_or_else(PoisonError::into_inner);
+        if quiet.0 == 0 {
+            let hook = Arc::new(panic::take_hook());
+            quiet.1 = Some(Arc::clone(&hook));
+            panic::set_hook(Box::new(move |info| {
+                if !CATCHING.get() {
+                    hook(info);
+                }
+            }));
+        }
+        quiet.0 += 1;
+        QuietPanics
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        let mut quiet = QUIET.lock().unwrap_or_else(PoisonError::into_inner);
+        quiet.0 -= 1;
+        if quiet.0 == 0 {
+            if let Some(hook) = quiet.1.take() {
+                // the quiet hook holds the other reference, dropped with it
+                drop(panic::take_hook());
+                match Arc::try_unwrap(hook) {
+                    Ok(hook) => panic::set_hook(hook),
+                    Err(hook) => panic::set_hook(Box::new(move |info| hook(info))),
+                }
+            }
+        }
+    }
+}
+
+/// Runs `f`, a panic becoming its message. The panic is not printed, the callers report it.
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    let _quiet = QuietPanics::new();
+    let catching = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(catching);
+    result.map_err(panic_message)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic payload".to_string(),
+        },
+    }
+}
+
+fn solved(result: Result<Solution, AocError>) -> Outcome {
+    match result {
+        Ok(solution) => Outcome::Solved(solution),
+        Err(err) => Outcome::Error(err),
+    }
+}
+
+/// Runs `solver`, reusing `shared_parsed` if a previous part of the day parsed the same type.
+fn run_shared(
+    solver: &Solver,
+    input: &str,
+    shared_parsed: &mut Option<Box<dyn Any>>,
+) -> Result<Solution, AocError> {
+    let mut part = solver.build();
+
+    let mut parse_time = None;
+    let parsed = match shared_parsed {
+        Some(parsed) if parsed.as_ref().type_id() == part.parsed_type() => parsed,
+        _ => {
+            let (parsed, elapsed) = timed(|| part.parse(input));
+            parse_time = Some(elapsed);
+            shared_parsed.insert(parsed?)
+        }
+    };
+
+    let (answer, solve_time) = timed(|| part.solve(parsed.as_ref()));
+    Ok(Solution {
+        answer: answer?,
+        parse_time,
+        solve_time,
+    })
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+pub fn format_reports(reports: &[RunReport], format: Format) -> String {
+    match format {
+        Format::Table => format_table(reports),
+        Format::Json => format_json(reports),
+        Format::Csv => format_csv(reports),
+    }
+}
+
+pub fn format_json(reports: &[RunReport]) -> String {
+    let reports = reports
+        .iter()
+        .map(|report| format!("  {}", report.to_json()))
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", reports.join(",\n"))
+}
+
+pub fn format_csv(reports: &[RunReport]) -> String {
+    let mut csv =
+        "year,day,part,answer,parse_ns,solve_ns,input_path,input_hash,error\n".to_string();
+    for report in reports {
+        let (answer, parse, solve, error) = match &report.outcome {
+            Outcome::Solved(solution) => (
+                solution.answer.to_string(),
+                solution
+                    .parse_time
+                    .map(|time| time.as_nanos().to_string())
+                    .unwrap_or_default(),
+                solution.solve_time.as_nanos().to_string(),
+                String::new(),
+            ),
+            outcome => (
+                String::new(),
+                String::new(),
+                String::new(),
+                outcome.to_string(),
+            ),
+        };
+        let record = [
+            report.year.to_string(),
+            report.day.to_string(),
+            report.part.to_string(),
+            answer,
+            parse,
+            solve,
+            report.input_path.to_string_lossy().into_owned(),
+            report.input_hash.map(format_hash).unwrap_or_default(),
+            error,
+        ];
+        csv.push_str(
+            &record
+                .iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn format_table(reports: &[RunReport]) -> String {
+    let mut total_parse = Duration::ZERO;
+    let mut total_solve = Duration::ZERO;
+    let rows: Vec<Vec<String>> = reports
+        .iter()
+        .map(|report| {
+            let (answer, parse, solve) = match &report.outcome {
+                Outcome::Solved(solution) => {
+                    total_parse += solution.parse_time.unwrap_or_default();
+                    total_solve += solution.solve_time;
+                    (
+                        table_answer(&solution.answer),
+                        solution
+                            .parse_time
+                            .map_or_else(|| "shared".to_string(), format_duration),
+                        format_duration(solution.solve_time),
+                    )
+                }
+                outcome => (outcome.to_string(), "-".to_string(), "-".to_string()),
+            };
+            vec![
+                report.year.to_string(),
+                report.day.to_string(),
+                report.part.to_string(),
+                answer,
+                parse,
+                solve,
+            ]
+        })
+        .collect();
+
+    let mut table = table::render(
+        &["Year", "Day", "Part", "Answer", "Parse", "Solve"],
+        &rows,
+        &[vec![
+            "Total".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            format_duration(total_parse),
+            format_duration(total_solve),
+        ]],
+    );
+    table.push_str(&format!(
+        "Grand total: {}\n",
+        format_duration(total_parse + total_solve)
+    ));
+    table
+}
+
+fn table_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::AsciiArt(lines) => format!("[ascii art, {} lines]", lines.len()),
+        answer => answer.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::AOCPart;
+
+    #[test]
+    fn run_solver_example() {
+        let solver = registry::find(2021, 1, 1).unwrap();
+        let solution =
+            run_solver(solver, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263").unwrap();
+        assert_eq!(solution.answer, Answer::from(7));
+        assert!(solution.parse_time.is_some());
+        assert_eq!(
+            run_solver(solver, "199\nabc").unwrap_err().to_string(),
+            "2021 day 1 line 2 column 1: invalid depth: invalid digit found in string"
+        );
+    }
+
+    fn report(day: u32, part: u32, outcome: Outcome) -> RunReport {
+        let input_hash = match outcome {
+            Outcome::Failed(_) => None,
+            _ => Some(0xc0ffee),
+        };
+        RunReport {
+            year: 2021,
+            day,
+            part,
+            input_path: input_path(2021, day),
+            input_hash,
+            outcome,
+        }
+    }
+
+    fn solution(answer: Answer, parse_micros: Option<u64>, solve_micros: u64) -> Outcome {
+        Outcome::Solved(Solution {
+            answer,
+            parse_time: parse_micros.map(Duration::from_micros),
+            solve_time: Duration::from_micros(solve_micros),
+        })
+    }
+
+    #[test]
+    fn run_one_example() {
+        let report = run_one(
+            registry::find(2021, 4, 2).unwrap(),
+            &InputSource::Example(1),
+            DEFAULT_TIMEOUT,
+        );
+        assert_eq!(report.year, 2021);
+        assert_eq!(
+            report.input_path,
+            PathBuf::from("subjects/2021/day_04.txt#example-1")
+        );
+        assert!(report.input_hash.is_some());
+        match report.outcome {
+            Outcome::Solved(solution) => assert_eq!(solution.answer, Answer::from(1924)),
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+    }
+
+    struct Sleeper {}
+
+    impl AOCPart for Sleeper {
+        type Parsed = ();
+
+        fn new() -> Self {
+            Sleeper {}
+        }
+
+        fn parse(&self, _input: &str) -> Result<Self::Parsed, AocError> {
+            Ok(())
+        }
+
+        fn solve(&mut self, _parsed: &Self::Parsed) -> Result<Answer, AocError> {
+            thread::sleep(Duration::from_secs(2));
+            Ok(0.into())
+        }
+    }
+
+    static SLOW_DAY: [Solver; 2] = [
+        Solver::new(2021, 25, 1, || Box::new(Sleeper::new())),
+        Solver::new(2021, 25, 2, || Box::new(crate::day_01::Part1::new())),
+    ];
+
+    #[test]
+    fn run_isolated_timeouts() {
+        let outcomes = run_isolated(&SLOW_DAY, "1\n2", Duration::from_millis(50));
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(outcomes[0], Outcome::Timeout(_)));
+        assert_eq!(outcomes[0].to_string(), "TIMEOUT after 50.00ms");
+        match &outcomes[1] {
+            Outcome::Solved(solution) => assert_eq!(solution.answer, Answer::from(1)),
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+    }
+
+    struct Panicker {}
+
+    impl AOCPart for Panicker {
+        type Parsed = ();
+
+        fn new() -> Self {
+            Panicker {}
+        }
+
+        fn parse(&self, _input: &str) -> Result<Self::Parsed, AocError> {
+            Ok(())
+        }
+
+        fn solve(&mut self, _parsed: &Self::Parsed) -> Result<Answer, AocError> {
+            panic!("gave up")
+        }
+    }
+
+    static PANICKING_DAY: [Solver; 2] = [
+        Solver::new(2021, 25, 1, || Box::new(Panicker::new())),
+        Solver::new(2021, 25, 2, || Box::new(crate::day_01::Part1::new())),
+    ];
+
+    #[test]
+    fn catch_panic_returns_the_message() {
+        assert_eq!(catch_panic(|| 7), Ok(7));
+        assert_eq!(
+            catch_panic(|| -> u32 { panic!("gave up") }),
+            Err("gave up".to_string())
+        );
+        assert!(!CATCHING.get());
+    }
+
+    #[test]
+    fn run_isolated_panics() {
+        let outcomes = run_isolated(&PANICKING_DAY, "1\n2", DEFAULT_TIMEOUT);
+        assert_eq!(outcomes[0].to_string(), "PANIC: gave up");
+        match &outcomes[1] {
+            Outcome::Solved(solution) => assert_eq!(solution.answer, Answer::from(1)),
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn input_hash_is_fnv1a() {
+        assert_eq!(format_hash(input_hash("")), "cbf29ce484222325");
+        assert_eq!(format_hash(input_hash("a")), "af63dc4c8601ec8c");
+        assert_eq!(format_hash(input_hash("foobar")), "85944171f73967e8");
+    }
+
+    #[test]
+    fn report_json() {
+        assert_eq!(
+            report(3, 2, solution(230.into(), None, 2)).to_json(),
+            r#"{"year":2021,"day":3,"part":2,"answer":230,"parse_ns":null,"solve_ns":2000,"input_path":"inputs/2021/day_03.txt","input_hash":"0000000000c0ffee"}"#
+        );
+        assert_eq!(
+            report(9, 1, Outcome::Failed("no \"input\"".to_string())).to_json(),
+            r#"{"year":2021,"day":9,"part":1,"error":"no \"input\"","input_path":"inputs/2021/day_09.txt","input_hash":null}"#
+        );
+    }
+
+    #[test]
+    fn format_json_example() {
+        assert_eq!(
+            format_json(&[
+                report(1, 1, solution(7.into(), Some(1), 2)),
+                report(1, 2, solution("x".into(), None, 3)),
+            ]),
+            r#"[
+  {"year":2021,"day":1,"part":1,"answer":7,"parse_ns":1000,"solve_ns":2000,"input_path":"inputs/2021/day_01.txt","input_hash":"0000000000c0ffee"},
+  {"year":2021,"day":1,"part":2,"answer":"x","parse_ns":null,"solve_ns":3000,"input_path":"inputs/2021/day_01.txt","input_hash":"0000000000c0ffee"}
+]
+"#
+        );
+    }
+
+    #[test]
+    fn format_csv_example() {
+        assert_eq!(
+            format_csv(&[
+                report(1, 1, solution(7.into(), Some(1), 2)),
+                report(1, 2, solution("a,\"b\"".into(), None, 3)),
+                report(9, 1, Outcome::Failed("no input".to_string())),
+            ]),
+            r#"year,day,part,answer,parse_ns,solve_ns,input_path,input_hash,error
+2021,1,1,7,1000,2000,inputs/2021/day_01.txt,0000000000c0ffee,
+2021,1,2,"a,""b""",,3000,inputs/2021/day_01.txt,0000000000c0ffee,
+2021,9,1,,,,inputs/2021/day_09.txt,,no input
+"#
+        );
+    }
+
+    #[test]
+    fn format_table_example() {
+        let reports = vec![
+            report(1, 1, solution(7.into(), Some(10), 5)),
+            report(
+                1,
+                2,
+                solution(
+                    Answer::AsciiArt(vec!["#.#".to_string(), ".#.".to_string()]),
+                    None,
+                    7,
+                ),
+            ),
+            report(9, 2, Outcome::Failed("no input".to_string())),
+        ];
+        assert_eq!(
+            format_table(&reports),
+            "Year  | Day | Part | Answer               | Parse   | Solve
+------+-----+------+----------------------+---------+--------
+2021  | 1   | 1    | 7                    | 10.00µs | 5.00µs
+2021  | 1   | 2    | [ascii art, 2 lines] | shared  | 7.00µs
+2021  | 9   | 2    | no input             | -       | -
+------+-----+------+----------------------+---------+--------
+Total |     |      |                      | 10.00µs | 12.00µs
+Grand total: 22.00µs
+"
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use crate::input::input_path;
+use crate::registry::{self, DEFAULT_YEAR};
+use crate::subjects::subject_path;
+use std::path::{Path, PathBuf};
+
+/// `day_source(7)`, built and tested with the crate so the generated code is known to compile.
+#[cfg(test)]
+mod golden_day;
+
+const REGISTRY_PATH: &str = "src/registry.rs";
+const REGISTRY_MACRO: &str = "register_years! {";
+
+/// `Part1` and `Part2` stubs sharing a line parser, to be filled in.
+pub fn day_source(day: u32) -> String {
+    let part = |name: &str, number: u32| {
+        format!(
+            "pub struct {name} {{}}
+
+impl AOCPart for {name} {{
+    type Parsed = Vec<String>;
+
+    fn new() -> Self {{
+        Self {{}}
+    }}
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {{
+        parse_lines(input)
+    }}
+
+    fn solve(&mut self, _lines: &Self::Parsed) -> Result<Answer, AocError> {{
+        Err(AocError::new(DAY, \"part {number} is not solved yet\"))
+    }}
+}}
+"
+        )
+    };
+    format!(
+        "use crate::aoc::{{AOCPart, Answer, AocError}};
+
+const DAY: u32 = {day};
+
+{}
+{}
+fn parse_lines(input: &str) -> Result<Vec<String>, AocError> {{
+    Ok(input.lines().map(String::from).collect())
+}}
+
+#[cfg(test)]
+mod test {{
+    use super::*;
+
+    const EXAMPLE: &str = \"\";
+
+    #[test]
+    fn parse_lines_example() {{
+        assert_eq!(parse_lines(EXAMPLE).unwrap().len(), EXAMPLE.lines().count());
+    }}
+}}
+",
+        part("Part1", 1),
+        part("Part2", 2)
+    )
+}
+
+/// Adds `pub mod <name>;` among the sorted module declarations, `None` if already declared.
+pub fn declare_module(source: &str, name: &str) -> Option<String> {
+    let declaration = format!("pub mod {};", name);
+    let mut lines: Vec<&str> = source.lines().collect();
+    if lines.contains(&declaration.as_str()) {
+        return None;
+    }
+    let modules: Vec<usize> = (0..lines.len())
+        .filter(|&index| lines[index].starts_with("pub mod "))
+        .collect();
+    let index = modules
+        .iter()
+        .find(|&&index| lines[index] > declaration.as_str())
+        .copied()
+        .or_else(|| modules.last().map(|last| last + 1))
+        .unwrap_or(lines.len());
+    lines.insert(index, &declaration);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Adds the day to the `register_years!` list of its year, creating the year if needed.
+pub fn register_day(source: &str, year: u32, day: u32) -> Result<String, String> {
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+    let start = lines
+        .iter()
+        .position(|line| line == REGISTRY_MACRO)
+        .ok_or_else(|| format!("no '{}' in {}", REGISTRY_MACRO, REGISTRY_PATH))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line == "}")
+            .ok_or_else(|| format!("unterminated '{}' in {}", REGISTRY_MACRO, REGISTRY_PATH))?;
+
+    let entry = format!("        {} => {},", day, registry::module_name(year, day));
+    let year_of = |line: &str| line.trim().strip_suffix(" => {")?.parse::<u32>().ok();
+    let block = (start..end).find(|&index| year_of(&lines[index]) == Some(year));
+    let Some(block) = block else {
+        let index = (start..end)
+            .find(|&index| year_of(&lines[index]).is_some_and(|other| other > year))
+            .unwrap_or(end);
+        let year_block = [format!("    {} => {{", year), entry, "    },".to_string()];
+        lines.splice(index..index, year_block);
+        return Ok(lines.join("\n") + "\n");
+    };
+
+    let mut index = block + 1;
+    while index < end && lines[index].trim() != "}," {
+        let registered = lines[index].trim().split(" => ").next();
+        match registered.and_then(|registered| registered.parse::<u32>().ok()) {
+            Some(registered) if registered == day => {
+                return Err(format!("{} day {} is already registered", year, day))
+            }
+            Some(registered) if registered > day => break,
+            _ => index += 1,
+        }
+    }
+    lines.insert(index, entry);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Where the module of a day is declared, the crate root or its year module.
+fn parent_module_path(year: u32) -> PathBuf {
+    match year {
+        DEFAULT_YEAR => PathBuf::from("src/lib.rs"),
+        year => PathBuf::from(format!("src/year_{}.rs", year)),
+    }
+}
+
+/// Creates the day module under `root`, declares and registers it, and creates empty input
+/// and subject files. Returns the paths created or modified.
+///
+/// Refuses to touch anything when the day already has a module; existing input and subject
+/// files are left as they are.
+pub fn scaffold(root: &Path, year: u32, day: u32) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("there is no day {}", day));
+    }
+    let source_path = root.join(registry::source_path(year, day));
+    if source_path.exists() {
+        return Err(format!("{} already exists", source_path.display()));
+    }
+
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read '{}': {}", path.display(), err))
+    };
+    let registry_path = root.join(REGISTRY_PATH);
+    let mut edits = vec![(
+        registry_path.clone(),
+        register_day(&read(&registry_path)?, year, day)?,
+    )];
+    let parent_path = root.join(parent_module_path(year));
+    let parent = match parent_path.exists() {
+        true => read(&parent_path)?,
+        false => String::new(),
+    };
+    let day_module = format!("day_{:02}", day);
+    match declare_module(&parent, &day_module) {
+        Some(parent) => edits.push((parent_path, parent)),
+        None => {
+            return Err(format!(
+                "{} already declared in {}",
+                day_module,
+                parent_path.display()
+            ))
+        }
+    }
+    if year != DEFAULT_YEAR {
+        let lib_path = root.join("src/lib.rs");
+        if let Some(lib) = declare_module(&read(&lib_path)?, &format!("year_{}", year)) {
+            edits.push((lib_path, lib));
+        }
+    }
+    edits.insert(0, (source_path, day_source(day)));
+
+    let mut touched = Vec::new();
+    for (path, content) in edits {
+        write(&path, &content)?;
+        touched.push(path);
+    }
+    for path in [input_path(year, day), subject_path(year, day)] {
+        let path = root.join(path);
+        if !path.exists() {
+            write(&path, "")?;
+            touched.push(path);
+        }
+    }
+    Ok(touched)
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    let error = |err: std::io::Error| format!("cannot write '{}': {}", path.display(), err);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(error)?;
+    }
+    std::fs::write(path, content).map_err(error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::{AOCPart, AocError};
+
+    const REGISTRY: &str = "use crate::aoc::AOCPart;
+
+register_years! {
+    2021 => {
+        1 => day_01,
+        4 => day_04,
+    },
+}
+";
+
+    #[test]
+    fn declare_modules_in_order() {
+        let lib = "pub mod aoc;\npub mod day_01;\npub mod day_04;\npub mod json;\n";
+        assert_eq!(
+            declare_module(lib, "day_02"),
+            Some(
+                "pub mod aoc;\npub mod day_01;\npub mod day_02;\npub mod day_04;\npub mod json;\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            declare_module(lib, "year_2022"),
+            Some(format!("{}pub mod year_2022;\n", lib))
+        );
+        assert_eq!(declare_module(lib, "day_04"), None);
+        assert_eq!(
+            declare_module("", "day_01"),
+            Some("pub mod day_01;\n".to_string())
+        );
+    }
+
+    #[test]
+    fn register_days_in_order() {
+        let registry = register_day(REGISTRY, 2021, 2).unwrap();
+        assert!(
+            registry.contains("        1 => day_01,\n        2 => day_02,\n        4 => day_04,\n")
+        );
+        let registry = register_day(&registry, 2021, 5).unwrap();
+        assert!(registry.contains("        4 => day_04,\n        5 => day_05,\n    },\n}\n"));
+        assert_eq!(
+            register_day(REGISTRY, 2021, 4),
+            Err("2021 day 4 is already registered".to_string())
+        );
+    }
+
+    #[test]
+    fn register_new_years() {
+        let registry = register_day(REGISTRY, 2022, 3).unwrap();
+        assert!(registry.ends_with(
+            "        4 => day_04,\n    },\n    2022 => {\n        3 => year_2022::day_03,\n    },\n}\n"
+        ));
+        let registry = register_day(&registry, 2020, 1).unwrap();
+        assert!(registry.contains(
+            "register_years! {\n    2020 => {\n        1 => year_2020::day_01,\n    },\n    2021 => {\n"
+        ));
+        assert!(register_day("", 2021, 1).is_err());
+    }
+
+    #[test]
+    fn day_source_stubs() {
+        assert_eq!(day_source(7), include_str!("scaffold/golden_day.rs"));
+
+        let error = |message: &str| Err(AocError::new(7, message));
+        assert_eq!(
+            golden_day::Part1::new().run("a\nb"),
+            error("part 1 is not solved yet")
+        );
+        assert_eq!(
+            golden_day::Part2::new().run("a\nb"),
+            error("part 2 is not solved yet")
+        );
+    }
+
+    #[test]
+    fn scaffold_new_days() {
+        let root = std::env::temp_dir().join(format!("aoc_scaffold_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        write(
+            &root.join("src/lib.rs"),
+            "pub mod aoc;\npub mod day_01;\npub mod day_04;\n",
+        )
+        .unwrap();
+        write(&root.join(REGISTRY_PATH), REGISTRY).unwrap();
+        write(&root.join("inputs/2021/day_02.txt"), "1\n2\n").unwrap();
+
+        assert_eq!(
+            scaffold(&root, 2021, 2),
+            Ok(vec![
+                root.join("src/day_02.rs"),
+                root.join("src/registry.rs"),
+                root.join("src/lib.rs"),
+                root.join("subjects/2021/day_02.txt"),
+            ])
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/day_02.rs")).unwrap(),
+            day_source(2)
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("inputs/2021/day_02.txt")).unwrap(),
+            "1\n2\n".to_string()
+        );
+        assert_eq!(
+            scaffold(&root, 2021, 2),
+            Err(format!(
+                "{} already exists",
+                root.join("src/day_02.rs").display()
+            ))
+        );
+        assert_eq!(
+            scaffold(&root, 2021, 26),
+            Err("there is no day 26".to_string())
+        );
+
+        assert_eq!(scaffold(&root, 2022, 1).map(|paths| paths.len()), Ok(6));
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/year_2022.rs")).unwrap(),
+            "pub mod day_01;\n".to_string()
+        );
+        assert!(std::fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .ends_with("pub mod day_04;\npub mod year_2022;\n"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+use crate::aoc::{AOCPart, Answer, AocError};
+
+const DAY: u32 = 7;
+
+pub struct Part1 {}
+
+impl AOCPart for Part1 {
+    type Parsed = Vec<String>;
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_lines(input)
+    }
+
+    fn solve(&mut self, _lines: &Self::Parsed) -> Result<Answer, AocError> {
+        Err(AocError::new(DAY, "part 1 is not solved yet"))
+    }
+}
+
+pub struct Part2 {}
+
+impl AOCPart for Part2 {
+    type Parsed = Vec<String>;
+
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_lines(input)
+    }
+
+    fn solve(&mut self, _lines: &Self::Parsed) -> Result<Answer, AocError> {
+        Err(AocError::new(DAY, "part 2 is not solved yet"))
+    }
+}
+
+fn parse_lines(input: &str) -> Result<Vec<String>, AocError> {
+    Ok(input.lines().map(String::from).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn parse_lines_example() {
+        assert_eq!(parse_lines(EXAMPLE).unwrap().len(), EXAMPLE.lines().count());
+    }
+}
@@ -0,0 +1,298 @@
+use crate::subjects::{self, Subject};
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+const BOLD: &str = "\x1b[1m";
+const TITLE: &str = "\x1b[1;33m";
+const EXAMPLE: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders the subject of `day`, only the given part if any. Without a part, a part two that
+/// is not unlocked yet is left out.
+pub fn render(
+    day: u32,
+    subject: &Subject,
+    part: Option<u32>,
+    width: usize,
+    color: bool,
+) -> Result<String, String> {
+    let style = |text: &str, code: &str| {
+        if color {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut output = style(&format!("--- Day {}: {} ---", day, subject.title), TITLE) + "\n";
+    if part != Some(2) {
+        output.push('\n');
+        output.push_str(&render_prose(&subject.part_one, width, color));
+    }
+    match (part, &subject.part_two) {
+        (Some(1), _) | (None, None) => {}
+        (_, Some(part_two)) => {
+            output.push_str(&format!("\n{}\n\n", style("--- Part Two ---", TITLE)));
+            output.push_str(&render_prose(part_two, width, color));
+        }
+        (_, None) => return Err(format!("day {} has no part two yet", day)),
+    }
+    Ok(output)
+}
+
+/// Prose paragraphs and list items are reflowed, data blocks are left untouched.
+fn render_prose(prose: &str, width: usize, color: bool) -> String {
+    let paragraphs: Vec<String> = subjects::paragraphs(prose)
+        .iter()
+        .map(|paragraph| {
+            if subjects::is_data(paragraph) {
+                let block = paragraph.join("\n");
+                return if color {
+                    format!("{}{}{}", EXAMPLE, block, RESET)
+                } else {
+                    block
+                };
+            }
+
+            let lines: Vec<String> = if paragraph[0].starts_with(char::is_whitespace) {
+                let indent = paragraph[0].len() - paragraph[0].trim_start().len();
+                list_items(paragraph)
+                    .iter()
+                    .flat_map(|item| wrap(item, width.saturating_sub(indent)))
+                    .map(|line| " ".repeat(indent) + &line)
+                    .collect()
+            } else {
+                wrap(&paragraph.join(" "), width)
+            };
+            let text = lines.join("\n");
+            if color {
+                highlight_numbers(&text)
+            } else {
+                text
+            }
+        })
+        .collect();
+    paragraphs.join("\n\n") + "\n"
+}
+
+/// Items of an indented list, one starts after a line ending a sentence.
+fn list_items(paragraph: &[&str]) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    for line in paragraph {
+        let line = line.trim();
+        match items.last_mut() {
+            Some(item)
+                if !item.ends_with(['.', ':', ';', '!', '?'])
+                    || !line.starts_with(char::is_uppercase) =>
+            {
+                item.push(' ');
+                item.push_str(line);
+            }
+            _ => items.push(line.to_string()),
+        }
+    }
+    items
+}
+
+/// Greedy word wrap, words longer than `width` get a line of their own.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Makes standalone numbers bold, leaving the likes of "CO2" or "5x5" alone.
+pub fn highlight_numbers(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut output = String::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let start = index;
+        while index < chars.len() && chars[index].is_ascii_digit() {
+            index += 1;
+        }
+        if start == index {
+            output.push(chars[index]);
+            index += 1;
+            continue;
+        }
+
+        let number: String = chars[start..index].iter().collect();
+        let standalone = (start == 0 || !chars[start - 1].is_alphanumeric())
+            && chars.get(index).is_none_or(|next| !next.is_alphanumeric());
+        if standalone {
+            output.push_str(&format!("{}{}{}", BOLD, number, RESET));
+        } else {
+            output.push_str(&number);
+        }
+    }
+    output
+}
+
+/// `COLUMNS`, then `tput`, then 80.
+pub fn terminal_width() -> usize {
+    terminal_size("COLUMNS", "cols").unwrap_or(80)
+}
+
+fn terminal_height() -> usize {
+    terminal_size("LINES", "lines").unwrap_or(24)
+}
+
+fn terminal_size(variable: &str, capability: &str) -> Option<usize> {
+    if let Some(size) = std::env::var(variable)
+        .ok()
+        .and_then(|size| size.parse().ok())
+    {
+        return Some(size);
+    }
+    let output = Command::new("tput")
+        .arg(capability)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    String::from_utf8(output.stdout).ok()?.trim().parse().ok()
+}
+
+pub fn stdout_is_terminal() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+/// Goes through `$PAGER` (`less -R` by default) when the text does not fit the terminal.
+pub fn page(text: &str) {
+    if stdout_is_terminal() && text.lines().count() >= terminal_height() {
+        let pager = std::env::var("PAGER").unwrap_or_else(|_| "less -R".to_string());
+        let mut words = pager.split_whitespace();
+        if let Some(program) = words.next() {
+            let child = Command::new(program)
+                .args(words)
+                .stdin(Stdio::piped())
+                .spawn();
+            if let Ok(mut child) = child {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // the pager quitting early closes the pipe, which is fine
+                    let _ = stdin.write_all(text.as_bytes());
+                }
+                if child.wait().is_ok() {
+                    return;
+                }
+            }
+        }
+    }
+    print!("{}", text);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SUBJECT: &str = "--- Day 3: Binary Diagnostic ---
+
+The diagnostic report (your puzzle input) consists of a list of binary
+numbers. For example, given the following diagnostic report:
+
+00100
+11110
+
+What is the power consumption of the submarine?
+
+--- Part Two ---
+
+The bit criteria depends on which type of rating value you want to find:
+
+    To find oxygen generator rating, determine the most common value
+    (0 or 1) in the current bit position.
+    To find CO2 scrubber rating, determine the least common value.
+";
+
+    #[test]
+    fn wrap_words() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(
+            wrap("a incomprehensibly b", 5),
+            vec!["a", "incomprehensibly", "b"]
+        );
+        assert_eq!(wrap("  ", 5), Vec::<String>::new());
+    }
+
+    #[test]
+    fn highlight_standalone_numbers() {
+        assert_eq!(
+            highlight_numbers("rate (22), CO2 and 5x5 grids: 198."),
+            "rate (\x1b[1m22\x1b[0m), CO2 and 5x5 grids: \x1b[1m198\x1b[0m."
+        );
+        assert_eq!(highlight_numbers("no numbers"), "no numbers");
+    }
+
+    #[test]
+    fn render_plain() {
+        let subject = Subject::parse(SUBJECT).unwrap();
+        assert_eq!(
+            render(3, &subject, None, 40, false).unwrap(),
+            "--- Day 3: Binary Diagnostic ---
+
+The diagnostic report (your puzzle
+input) consists of a list of binary
+numbers. For example, given the
+following diagnostic report:
+
+00100
+11110
+
+What is the power consumption of the
+submarine?
+
+--- Part Two ---
+
+The bit criteria depends on which type
+of rating value you want to find:
+
+    To find oxygen generator rating,
+    determine the most common value (0
+    or 1) in the current bit position.
+    To find CO2 scrubber rating,
+    determine the least common value.
+"
+        );
+    }
+
+    #[test]
+    fn render_single_part() {
+        let subject = Subject::parse(SUBJECT).unwrap();
+        let part_one = render(3, &subject, Some(1), 80, false).unwrap();
+        assert!(part_one.ends_with("of the submarine?\n"));
+        let part_two = render(3, &subject, Some(2), 80, false).unwrap();
+        assert!(part_two.starts_with("--- Day 3: Binary Diagnostic ---\n\n--- Part Two ---\n"));
+
+        let colored = render(3, &subject, Some(1), 80, true).unwrap();
+        assert!(colored.starts_with("\x1b[1;33m--- Day 3: Binary Diagnostic ---\x1b[0m\n"));
+        assert!(colored.contains("\x1b[36m00100\n11110\x1b[0m"));
+
+        let without_part_two = Subject {
+            part_two: None,
+            ..subject
+        };
+        assert_eq!(
+            render(3, &without_part_two, Some(2), 80, false),
+            Err("day 3 has no part two yet".to_string())
+        );
+        assert_eq!(
+            render(3, &without_part_two, None, 80, false),
+            render(3, &without_part_two, Some(1), 80, false)
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use std::path::PathBuf;
+
+const PART_TWO_HEADER: &str = "--- Part Two ---";
+
+pub fn subject_path(year: u32, day: u32) -> PathBuf {
+    PathBuf::from(format!("subjects/{}/day_{:02}.txt", year, day))
+}
+
+/// A puzzle text, as found in `subjects/YYYY/day_XX.txt`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Subject {
+    /// Without the day, e.g. "Giant Squid".
+    pub title: String,
+    pub part_one: String,
+    /// `None` when the second part is not unlocked yet.
+    pub part_two: Option<String>,
+    pub examples: Vec<Example>,
+}
+
+/// An example input of a subject.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Example {
+    pub input: String,
+    /// Answer for each part, when the prose concludes on this example.
+    pub answers: [Option<String>; 2],
+}
+
+impl Subject {
+    pub fn load(year: u32, day: u32) -> Result<Subject, String> {
+        let path = subject_path(year, day);
+        let text = std::fs::read_to_string(&path)
+            .map_err(|err| format!("cannot read '{}': {}", path.display(), err))?;
+        Subject::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    pub fn parse(text: &str) -> Result<Subject, String> {
+        let text = text.trim_start();
+        let (title_line, body) = text.split_once('\n').unwrap_or((text, ""));
+        let title = title_line
+            .trim()
+            .strip_prefix("--- ")
+            .and_then(|title| title.strip_suffix(" ---"))
+            .and_then(|title| title.split_once(": "))
+            .map(|(_, title)| title.to_string())
+            .ok_or("missing '--- Day N: Title ---' line")?;
+
+        let (part_one, part_two) = match body.split_once(PART_TWO_HEADER) {
+            Some((part_one, part_two)) => (part_one.trim(), Some(part_two.trim())),
+            None => (body.trim(), None),
+        };
+
+        let part_one_blocks = example_blocks(part_one);
+        let part_one_count = part_one_blocks.len();
+        let mut examples: Vec<Example> = part_one_blocks
+            .into_iter()
+            .chain(part_two.map(example_blocks).unwrap_or_default())
+            .map(|input| Example {
+                input,
+                answers: [None, None],
+            })
+            .collect();
+        // A part concludes on its last example, or on the last one of part one when it
+        // reuses it ("the same example above").
+        if let Some(example) = examples[..part_one_count].last_mut() {
+            example.answers[0] = example_answer(part_one);
+        }
+        if let Some(example) = examples.last_mut() {
+            example.answers[1] = part_two.and_then(example_answer);
+        }
+
+        Ok(Subject {
+            title,
+            part_one: part_one.to_string(),
+            part_two: part_two.map(str::to_string),
+            examples,
+        })
+    }
+
+    /// Indices of the examples with a known answer for `part` (1 or 2), with that answer.
+    pub fn answered_examples(&self, part: u32) -> Vec<(usize, &str)> {
+        let Some(index) = usize::try_from(part)
+            .ok()
+            .and_then(|part| part.checked_sub(1))
+            .filter(|index| *index < 2)
+        else {
+            return vec![];
+        };
+        self.examples
+            .iter()
+            .enumerate()
+            .filter_map(|(position, example)| Some((position, example.answers[index].as_deref()?)))
+            .collect()
+    }
+}
+
+/// The last number of the paragraph before the closing question of a part, where the
+/// worked example concludes, e.g. "... to get the final score, 188 * 24 = 4512."
+fn example_answer(prose: &str) -> Option<String> {
+    let paragraphs = paragraphs(prose);
+    let [.., conclusion, _question] = &paragraphs[..] else {
+        return None;
+    };
+    conclusion
+        .iter()
+        .rev()
+        .flat_map(|line| line.rsplit(|c: char| !c.is_ascii_digit()))
+        .find(|number| !number.is_empty())
+        .map(str::to_string)
+}
+
+/// Puzzle inputs given as examples in a subject, in order of appearance.
+///
+/// An example is the run of data paragraphs (no prose line) following a paragraph that
+/// mentions an example and ends with a colon, e.g. "For example, given the following report:".
+pub fn example_blocks(subject: &str) -> Vec<String> {
+    let paragraphs = paragraphs(subject);
+
+    let mut examples = Vec::new();
+    let mut index = 0;
+    while index < paragraphs.len() {
+        if !introduces_example(&paragraphs[index]) {
+            index += 1;
+            continue;
+        }
+        index += 1;
+
+        let data: Vec<String> = paragraphs[index..]
+            .iter()
+            .take_while(|paragraph| is_data(paragraph))
+            .map(|paragraph| paragraph.join("\n"))
+            .collect();
+        if !data.is_empty() {
+            index += data.len();
+            examples.push(data.join("\n\n") + "\n");
+        }
+    }
+    examples
+}
+
+pub(crate) fn paragraphs(text: &str) -> Vec<Vec<&str>> {
+    let mut paragraphs = vec![];
+    let mut paragraph = vec![];
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !paragraph.is_empty() {
+                paragraphs.push(std::mem::take(&mut paragraph));
+            }
+        } else {
+            paragraph.push(line.trim_end());
+        }
+    }
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph);
+    }
+    paragraphs
+}
+
+fn introduces_example(paragraph: &[&str]) -> bool {
+    paragraph
+        .last()
+        .is_some_and(|last_line| last_line.ends_with(':'))
+        && paragraph
+            .iter()
+            .any(|line| line.to_lowercase().contains("example"))
+}
+
+pub(crate) fn is_data(paragraph: &[&str]) -> bool {
+    paragraph.iter().all(|line| {
+        line.split_whitespace()
+            .filter(|word| word.chars().all(char::is_alphabetic))
+            .count()
+            < 3
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SUBJECT: &str = "--- Day 3: Binary Diagnostic ---
+
+The diagnostic report (your puzzle input) consists of a list of binary numbers.
+For example, given the following diagnostic report:
+
+00100
+11110
+
+Multiplying the gamma rate (22) by the epsilon
+rate (9) produces the power consumption, 198.
+
+What is the power consumption of the submarine?
+
+--- Part Two ---
+
+Then, to determine the CO2 scrubber rating value from the same example above:
+
+    Start again with all 12 numbers.
+
+Finally, multiply the oxygen generator rating (23) by the CO2 scrubber rating (10) to get 230.
+
+What is the life support rating of the submarine?
+";
+
+    #[test]
+    fn parse_subject() {
+        let subject = Subject::parse(SUBJECT).unwrap();
+        assert_eq!(subject.title, "Binary Diagnostic");
+        assert!(subject.part_one.starts_with("The diagnostic report"));
+        assert!(subject.part_one.ends_with("of the submarine?"));
+        assert!(subject
+            .part_two
+            .as_deref()
+            .is_some_and(|part_two| part_two.starts_with("Then, to determine")));
+        assert_eq!(
+            subject.examples,
+            vec![Example {
+                input: "00100\n11110\n".to_string(),
+                answers: [Some("198".to_string()), Some("230".to_string())],
+            }]
+        );
+        assert_eq!(subject.answered_examples(2), vec![(0, "230")]);
+        assert_eq!(subject.answered_examples(3), vec![]);
+    }
+
+    #[test]
+    fn parse_subject_without_part_two() {
+        let part_one = SUBJECT.split(PART_TWO_HEADER).next().unwrap();
+        let subject = Subject::parse(part_one).unwrap();
+        assert_eq!(subject.part_two, None);
+        assert_eq!(subject.examples[0].answers, [Some("198".to_string()), None]);
+
+        assert_eq!(
+            Subject::parse("").unwrap_err(),
+            "missing '--- Day N: Title ---' line"
+        );
+    }
+
+    #[test]
+    fn part_two_concludes_on_its_own_example() {
+        let subject = Subject::parse(
+            "--- Day 9: Test ---
+
+For example, given the following report:
+
+1
+2
+
+Adding them up gives a total of 3.
+
+What is the sum?
+
+--- Part Two ---
+
+In this other example:
+
+4
+5
+
+Multiplying them together gives a product of 20.
+
+What is the product?
+",
+        )
+        .unwrap();
+        let inputs: Vec<_> = subject
+            .examples
+            .iter()
+            .map(|example| example.input.as_str())
+            .collect();
+        assert_eq!(inputs, vec!["1\n2\n", "4\n5\n"]);
+        assert_eq!(subject.answered_examples(1), vec![(0, "3")]);
+        assert_eq!(subject.answered_examples(2), vec![(1, "20")]);
+    }
+
+    #[test]
+    fn load_subject() {
+        let subject = Subject::load(2021, 4).unwrap();
+        assert_eq!(subject.title, "Giant Squid");
+        assert_eq!(subject.examples.len(), 1);
+        assert_eq!(
+            subject.examples[0].answers,
+            [Some("4512".to_string()), Some("1924".to_string())]
+        );
+    }
+
+    #[test]
+    fn example_blocks_single_paragraph() {
+        assert_eq!(
+            example_blocks(
+                "Some prose about the puzzle. For
+example, given the following report:
+
+00100
+11110
+
+Considering only the first bit of each number, there are five 0 bits.
+"
+            ),
+            vec!["00100\n11110\n"]
+        );
+    }
+
+    #[test]
+    fn example_blocks_multiple_paragraphs() {
+        assert_eq!(
+            example_blocks(
+                "For example:
+
+7,4,9
+
+22 13
+ 8  2
+
+ 3 15
+ 9 18
+
+After the first numbers are drawn, the boards are marked as follows:
+
+22 13          3 15
+ 8  2          9 18
+
+In this other example, commands are:
+
+forward 5
+down 5
+"
+            ),
+            vec![
+                "7,4,9\n\n22 13\n 8  2\n\n 3 15\n 9 18\n",
+                "forward 5\ndown 5\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn example_blocks_ignores_prose_lists() {
+        assert_eq!(
+            example_blocks(
+                "For example, using the same example from above:
+
+    Start with all 12 numbers and consider only the first bit of each number.
+"
+            ),
+            Vec::<String>::new()
+        );
+    }
+}
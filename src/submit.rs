@@ -0,0 +1,523 @@
+use crate::http;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+pub const DEFAULT_PATH: &str = "submissions.csv";
+
+const HEADER: &str = "timestamp,year,day,part,verdict,wait_s,answer";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint.
+    Wrong,
+    /// Rejected because of the cool-down, the answer was not checked.
+    TooSoon,
+    /// The part was solved before, possibly outside of this tool.
+    AlreadySolved,
+    /// Response text that could not be interpreted, never recorded.
+    Unknown(String),
+}
+
+impl Verdict {
+    fn code(&self) -> &str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too_high",
+            Verdict::TooLow => "too_low",
+            Verdict::Wrong => "wrong",
+            Verdict::TooSoon => "too_soon",
+            Verdict::AlreadySolved => "solved",
+            Verdict::Unknown(_) => "unknown",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Verdict> {
+        Some(match code {
+            "correct" => Verdict::Correct,
+            "too_high" => Verdict::TooHigh,
+            "too_low" => Verdict::TooLow,
+            "wrong" => Verdict::Wrong,
+            "too_soon" => Verdict::TooSoon,
+            "solved" => Verdict::AlreadySolved,
+            _ => return None,
+        })
+    }
+
+    fn is_rejection(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooSoon => write!(f, "too soon"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Unknown(text) => write!(f, "unrecognized response: {}", text),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Submission {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub verdict: Verdict,
+    /// Cool-down before the next submission, in seconds.
+    pub wait: u64,
+    pub answer: String,
+}
+
+impl Submission {
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{}",
+            self.timestamp,
+            self.year,
+            self.day,
+            self.part,
+            self.verdict.code(),
+            self.wait,
+            self.answer
+        )
+    }
+
+    /// The answer comes last, it may contain commas.
+    fn from_csv(line: &str) -> Option<Submission> {
+        let fields: Vec<&str> = line.splitn(7, ',').collect();
+        let [timestamp, year, day, part, verdict, wait, answer] = fields[..] else {
+            return None;
+        };
+        Some(Submission {
+            timestamp: timestamp.parse().ok()?,
+            year: year.parse().ok()?,
+            day: day.parse().ok()?,
+            part: part.parse().ok()?,
+            verdict: Verdict::from_code(verdict)?,
+            wait: wait.parse().ok()?,
+            answer: answer.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SubmissionLog {
+    pub submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    /// A missing file is an empty log.
+    pub fn load(path: &Path) -> Result<SubmissionLog, String> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => SubmissionLog::parse(&content)
+                .map_err(|line| format!("{}: invalid entry on line {}", path.display(), line)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(SubmissionLog::default()),
+            Err(err) => Err(format!("cannot read '{}': {}", path.display(), err)),
+        }
+    }
+
+    /// On failure, returns the number of the offending line.
+    pub fn parse(content: &str) -> Result<SubmissionLog, usize> {
+        let submissions = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && *line != HEADER)
+            .map(|(index, line)| Submission::from_csv(line).ok_or(index + 1))
+            .collect::<Result<_, _>>()?;
+        Ok(SubmissionLog { submissions })
+    }
+
+    pub fn append(path: &Path, submission: &Submission) -> Result<(), String> {
+        let error = |err: std::io::Error| format!("cannot write '{}': {}", path.display(), err);
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(error)?;
+        if is_new {
+            writeln!(file, "{}", HEADER).map_err(error)?;
+        }
+        writeln!(file, "{}", submission.to_csv()).map_err(error)
+    }
+
+    /// The cool-down applies to all days.
+    pub fn cooldown_until(&self) -> Option<u64> {
+        self.submissions
+            .iter()
+            .map(|submission| submission.timestamp + submission.wait)
+            .max()
+    }
+
+    /// Refuses submissions that are known to be pointless before they reach the server.
+    pub fn check(
+        &self,
+        year: u32,
+        day: u32,
+        part: u32,
+        answer: &str,
+        now: u64,
+    ) -> Result<(), String> {
+        let previous = self.submissions.iter().filter(|submission| {
+            (submission.year, submission.day, submission.part) == (year, day, part)
+        });
+        for submission in previous {
+            match &submission.verdict {
+                Verdict::Correct => {
+                    return Err(format!(
+                        "{} day {} part {} is already solved with {}",
+                        year, day, part, submission.answer
+                    ))
+                }
+                Verdict::AlreadySolved => {
+                    return Err(format!(
+                        "{} day {} part {} is already solved",
+                        year, day, part
+                    ))
+                }
+                verdict if verdict.is_rejection() && submission.answer == answer => {
+                    return Err(format!(
+                        "{} was already rejected for {} day {} part {} ({})",
+                        answer, year, day, part, verdict
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        match self.cooldown_until() {
+            Some(until) if until > now => {
+                Err(format!("wait {}s before submitting again", until - now))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Where answers are posted to, and as whom.
+#[derive(Debug, Clone, Copy)]
+pub struct Server<'a> {
+    pub base_url: &'a str,
+    pub session: &'a str,
+}
+
+pub fn answer_url(base_url: &str, year: u32, day: u32) -> String {
+    format!(
+        "{}/{}/day/{}/answer",
+        base_url.trim_end_matches('/'),
+        year,
+        day
+    )
+}
+
+/// Text of an html page, tags removed and whitespace collapsed.
+fn page_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn parse_verdict(text: &str) -> Verdict {
+    if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("That's not the right answer") {
+        if text.contains("too high") {
+            Verdict::TooHigh
+        } else if text.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown(text.chars().take(200).collect())
+    }
+}
+
+/// Either "You have 1m 23s left to wait" or "please wait 5 minutes before trying again".
+pub fn parse_wait(text: &str) -> Option<Duration> {
+    if let Some((_, rest)) = text.split_once("You have ") {
+        if let Some((left, _)) = rest.split_once(" left to wait") {
+            return left
+                .split_whitespace()
+                .map(|token| {
+                    let (number, unit) = token.split_at(token.find(|c: char| !c.is_ascii_digit())?);
+                    let unit = match unit {
+                        "s" => 1,
+                        "m" => 60,
+                        "h" => 3600,
+                        _ => return None,
+                    };
+                    Some(number.parse::<u64>().ok()? * unit)
+                })
+                .sum::<Option<u64>>()
+                .map(Duration::from_secs);
+        }
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words.windows(3).find_map(|words| {
+        if words[0] != "wait" {
+            return None;
+        }
+        let number = match words[1] {
+            "a" | "one" => 1,
+            "two" => 2,
+            "three" => 3,
+            "four" => 4,
+            "five" => 5,
+            "ten" => 10,
+            number => number.parse().ok()?,
+        };
+        let unit = match words[2].trim_end_matches(['.', ',']) {
+            "second" | "seconds" => 1,
+            "minute" | "minutes" => 60,
+            "hour" | "hours" => 3600,
+            _ => return None,
+        };
+        Some(Duration::from_secs(number * unit))
+    })
+}
+
+/// Posts `answer` unless the log already rules it out, recording the verdict.
+pub fn submit(
+    year: u32,
+    day: u32,
+    part: u32,
+    answer: &str,
+    server: Server,
+    log_path: &Path,
+    now: u64,
+) -> Result<Submission, String> {
+    SubmissionLog::load(log_path)?.check(year, day, part, answer, now)?;
+
+    let url = answer_url(server.base_url, year, day);
+    let level = part.to_string();
+    let response = http::post_form(
+        &url,
+        server.session,
+        &[("level", &level), ("answer", answer)],
+    )?;
+    if !response.is_success() {
+        return Err(format!("{} answered {}", url, response.status));
+    }
+
+    let text = page_text(&response.body);
+    let submission = Submission {
+        timestamp: now,
+        year,
+        day,
+        part,
+        verdict: parse_verdict(&text),
+        wait: parse_wait(&text).map_or(0, |wait| wait.as_secs()),
+        answer: answer.to_string(),
+    };
+    if !matches!(submission.verdict, Verdict::Unknown(_)) {
+        SubmissionLog::append(log_path, &submission)?;
+    }
+    Ok(submission)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn submission(
+        timestamp: u64,
+        part: u32,
+        verdict: Verdict,
+        wait: u64,
+        answer: &str,
+    ) -> Submission {
+        Submission {
+            timestamp,
+            year: 2021,
+            day: 4,
+            part,
+            verdict,
+            wait,
+            answer: answer.to_string(),
+        }
+    }
+
+    fn page(article: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            article
+        )
+    }
+
+    #[test]
+    fn parse_verdicts() {
+        assert_eq!(
+            parse_verdict("That's the right answer! You are one gold star closer."),
+            Verdict::Correct
+        );
+        assert_eq!(
+            parse_verdict("That's not the right answer; your answer is too high."),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            parse_verdict("That's not the right answer; your answer is too low."),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            parse_verdict("That's not the right answer. If you're stuck, ..."),
+            Verdict::Wrong
+        );
+        assert_eq!(
+            parse_verdict("You gave an answer too recently; you have to wait."),
+            Verdict::TooSoon
+        );
+        assert_eq!(
+            parse_verdict("You don't seem to be solving the right level."),
+            Verdict::AlreadySolved
+        );
+        assert_eq!(
+            parse_verdict("Service unavailable"),
+            Verdict::Unknown("Service unavailable".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_waits() {
+        assert_eq!(
+            parse_wait("You have 1m 23s left to wait."),
+            Some(Duration::from_secs(83))
+        );
+        assert_eq!(
+            parse_wait("You have 45s left to wait."),
+            Some(Duration::from_secs(45))
+        );
+        assert_eq!(
+            parse_wait("Please wait one minute before trying again."),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_wait("please wait 5 minutes before trying again."),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(parse_wait("That's the right answer!"), None);
+    }
+
+    #[test]
+    fn page_text_strips_tags() {
+        assert_eq!(
+            page_text("<article><p>That's <em>not</em>\n  the right answer</p></article>"),
+            "That's not the right answer"
+        );
+    }
+
+    #[test]
+    fn submission_csv_roundtrip() {
+        let submission = submission(1_700_000_000, 2, Verdict::TooLow, 60, "1,2,3");
+        assert_eq!(submission.to_csv(), "1700000000,2021,4,2,too_low,60,1,2,3");
+        assert_eq!(
+            Submission::from_csv(&submission.to_csv()),
+            Some(submission.clone())
+        );
+        assert_eq!(Submission::from_csv("1700000000,4,2,too_low,60"), None);
+        assert_eq!(Submission::from_csv("1,2021,4,2,maybe,0,7"), None);
+        assert_eq!(
+            SubmissionLog::parse(&format!("{}\n1,2021,4,1,correct,0,7\nnope", HEADER)),
+            Err(3)
+        );
+    }
+
+    #[test]
+    fn check_rules_out_known_answers() {
+        let log = SubmissionLog {
+            submissions: vec![
+                submission(100, 1, Verdict::TooHigh, 60, "5000"),
+                submission(200, 1, Verdict::Correct, 0, "4512"),
+                submission(300, 2, Verdict::TooSoon, 0, "2000"),
+                submission(400, 2, Verdict::TooLow, 300, "1000"),
+            ],
+        };
+        assert_eq!(
+            log.check(2021, 4, 1, "4600", 1000),
+            Err("2021 day 4 part 1 is already solved with 4512".to_string())
+        );
+        assert_eq!(
+            log.check(2021, 4, 2, "1000", 1000),
+            Err("1000 was already rejected for 2021 day 4 part 2 (too low)".to_string())
+        );
+        assert_eq!(log.check(2021, 4, 2, "2000", 1000), Ok(()));
+        assert_eq!(log.check(2020, 4, 1, "4512", 1000), Ok(()));
+        assert_eq!(
+            log.check(2021, 4, 2, "1924", 650),
+            Err("wait 50s before submitting again".to_string())
+        );
+        assert_eq!(log.check(2021, 5, 1, "1", 700), Ok(()));
+    }
+
+    fn server(base_url: &str) -> Server<'_> {
+        Server {
+            base_url,
+            session: "token",
+        }
+    }
+
+    #[test]
+    fn submit_to_local_server() {
+        let path = std::env::temp_dir().join(format!("aoc_submissions_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let (base_url, request) = http::serve_once(&page(
+            "That's not the right answer; your answer is too high. Please wait one minute before trying again.",
+        ));
+        assert_eq!(
+            submit(2021, 4, 1, "5000", server(&base_url), &path, 100),
+            Ok(submission(100, 1, Verdict::TooHigh, 60, "5000"))
+        );
+        let request = request.join().unwrap();
+        assert!(request.starts_with("POST /2021/day/4/answer HTTP/1.1\r\n"));
+        assert!(request.ends_with("level=1&answer=5000"));
+
+        // ruled out locally, no server is listening anymore
+        assert_eq!(
+            submit(2021, 4, 1, "4512", server(&base_url), &path, 130),
+            Err("wait 30s before submitting again".to_string())
+        );
+        assert_eq!(
+            submit(2021, 4, 1, "5000", server(&base_url), &path, 200),
+            Err("5000 was already rejected for 2021 day 4 part 1 (too high)".to_string())
+        );
+
+        let (base_url, _) = http::serve_once(&page("That's the right answer!"));
+        assert_eq!(
+            submit(2021, 4, 1, "4512", server(&base_url), &path, 200).map(|s| s.verdict),
+            Ok(Verdict::Correct)
+        );
+        let log = SubmissionLog::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(log.submissions.len(), 2);
+    }
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+/// Renders left aligned columns, with a rule under the header and another above the footer.
+pub fn render(header: &[&str], rows: &[Vec<String>], footer: &[Vec<String>]) -> String {
+    let header: Vec<String> = header.iter().map(|cell| cell.to_string()).collect();
+
+    let mut widths = vec![0; header.len()];
+    for row in std::iter::once(&header).chain(rows).chain(footer) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>()
+        .join("-+-");
+
+    let mut table = render_row(&header, &widths);
+    table.push_str(&separator);
+    table.push('\n');
+    for row in rows {
+        table.push_str(&render_row(row, &widths));
+    }
+    if !footer.is_empty() {
+        table.push_str(&separator);
+        table.push('\n');
+        for row in footer {
+            table.push_str(&render_row(row, &widths));
+        }
+    }
+    table
+}
+
+fn render_row(row: &[String], widths: &[usize]) -> String {
+    let line = row
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    format!("{}\n", line.trim_end())
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    #[test]
+    fn render_example() {
+        assert_eq!(
+            render(
+                &["Day", "Answer"],
+                &[row(&["1", "1624"]), row(&["12", ""])],
+                &[row(&["Total", "x"])]
+            ),
+            "Day   | Answer
+------+-------
+1     | 1624
+12    |
+------+-------
+Total | x
+"
+        );
+        assert_eq!(render(&["A", "B"], &[], &[]), "A | B\n--+--\n");
+    }
+}
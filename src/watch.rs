@@ -0,0 +1,237 @@
+use crate::input::input_path;
+use crate::registry;
+use crate::subjects::subject_path;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct WatchConfig {
+    pub year: u32,
+    pub day: u32,
+    pub interval: Duration,
+}
+
+/// Outcome of running one part, the printed answer or the error output.
+pub type PartResult = Result<String, String>;
+
+pub fn watched_paths(year: u32, day: u32) -> Vec<PathBuf> {
+    vec![
+        registry::source_path(year, day),
+        input_path(year, day),
+        subject_path(year, day),
+    ]
+}
+
+/// `None` for files that do not exist (yet).
+pub fn modification_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+        })
+        .collect()
+}
+
+pub fn changed_paths<'a>(
+    paths: &'a [PathBuf],
+    before: &[Option<SystemTime>],
+    after: &[Option<SystemTime>],
+) -> Vec<&'a PathBuf> {
+    paths
+        .iter()
+        .zip(before.iter().zip(after))
+        .filter(|(_, (before, after))| before != after)
+        .map(|(path, _)| path)
+        .collect()
+}
+
+/// Goes through cargo so that source changes are rebuilt.
+fn cargo(args: &[&str]) -> Result<Output, String> {
+    Command::new("cargo")
+        .args(args)
+        .output()
+        .map_err(|err| format!("cannot run cargo: {}", err))
+}
+
+fn output_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end().to_string()
+}
+
+/// Runs the unit tests of the day module, returning their output on failure.
+pub fn run_tests(year: u32, day: u32) -> Result<(), String> {
+    let filter = format!("{}::", registry::module_name(year, day));
+    let output = cargo(&["test", "--quiet", "--lib", &filter])?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}\n{}",
+            output_text(&output.stdout),
+            output_text(&output.stderr)
+        )
+        .trim()
+        .to_string())
+    }
+}
+
+pub fn run_part(year: u32, day: u32, part: u32) -> PartResult {
+    let (year, day, part) = (year.to_string(), day.to_string(), part.to_string());
+    let output = cargo(&[
+        "run", "--quiet", "--", "run", "--year", &year, "--day", &day, "--part", &part,
+    ])?;
+    if output.status.success() {
+        Ok(output_text(&output.stdout))
+    } else {
+        Err(output_text(&output.stderr))
+    }
+}
+
+/// Describes the answer of a part relative to the previous run.
+pub fn diff_answer(part: u32, previous: Option<&PartResult>, current: &PartResult) -> String {
+    let answer = match current {
+        Ok(answer) => answer,
+        Err(err) => return format!("part {}: failed\n{}\n", part, err),
+    };
+    let previous = match previous {
+        Some(Ok(previous)) => previous,
+        _ => return format!("part {}: {}\n", part, answer),
+    };
+
+    if previous == answer {
+        format!("part {}: {} (unchanged)\n", part, answer)
+    } else if !previous.contains('\n') && !answer.contains('\n') {
+        format!("part {}: {} -> {}\n", part, previous, answer)
+    } else {
+        let mut diff = format!("part {}: changed\n", part);
+        let (old, new): (Vec<_>, Vec<_>) = (previous.lines().collect(), answer.lines().collect());
+        for index in 0..old.len().max(new.len()) {
+            match (old.get(index), new.get(index)) {
+                (Some(old), Some(new)) if old == new => diff.push_str(&format!("  {}\n", old)),
+                (old, new) => {
+                    if let Some(old) = old {
+                        diff.push_str(&format!("- {}\n", old));
+                    }
+                    if let Some(new) = new {
+                        diff.push_str(&format!("+ {}\n", new));
+                    }
+                }
+            }
+        }
+        diff
+    }
+}
+
+/// Polls the day files forever, rerunning tests and both parts on every change.
+pub fn watch(config: WatchConfig) -> ! {
+    let paths = watched_paths(config.year, config.day);
+    let mut previous: Vec<Option<PartResult>> = vec![None, None];
+    let mut times = modification_times(&paths);
+
+    println!("watching {}", describe_paths(&paths));
+    loop {
+        match run_tests(config.year, config.day) {
+            Ok(()) => {
+                println!("tests passed");
+                for (part, previous) in (1..).zip(previous.iter_mut()) {
+                    let current = run_part(config.year, config.day, part);
+                    print!("{}", diff_answer(part, previous.as_ref(), &current));
+                    *previous = Some(current);
+                }
+            }
+            Err(output) => println!("tests failed\n{}", output),
+        }
+
+        loop {
+            std::thread::sleep(config.interval);
+            let now = modification_times(&paths);
+            let changed = changed_paths(&paths, &times, &now);
+            if !changed.is_empty() {
+                println!("\nchanged: {}", describe_paths(changed));
+                times = now;
+                break;
+            }
+        }
+    }
+}
+
+fn describe_paths<'a, I: IntoIterator<Item = &'a PathBuf>>(paths: I) -> String {
+    paths
+        .into_iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn watched_paths_for_day() {
+        assert_eq!(
+            watched_paths(2021, 4),
+            vec![
+                PathBuf::from("src/day_04.rs"),
+                PathBuf::from("inputs/2021/day_04.txt"),
+                PathBuf::from("subjects/2021/day_04.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn detect_changes() {
+        let paths = watched_paths(2021, 1);
+        let t0 = SystemTime::UNIX_EPOCH;
+        let t1 = t0 + Duration::from_secs(1);
+        assert!(changed_paths(&paths, &[Some(t0), None, None], &[Some(t0), None, None]).is_empty());
+        assert_eq!(
+            changed_paths(
+                &paths,
+                &[Some(t0), None, Some(t0)],
+                &[Some(t1), Some(t0), Some(t0)]
+            ),
+            vec![&paths[0], &paths[1]]
+        );
+        assert_eq!(
+            modification_times(&[PathBuf::from("no/such/file")]),
+            vec![None]
+        );
+    }
+
+    #[test]
+    fn diff_single_line_answers() {
+        let ok = |answer: &str| Ok(answer.to_string());
+        assert_eq!(diff_answer(1, None, &ok("1624")), "part 1: 1624\n");
+        assert_eq!(
+            diff_answer(1, Some(&ok("1624")), &ok("1624")),
+            "part 1: 1624 (unchanged)\n"
+        );
+        assert_eq!(
+            diff_answer(2, Some(&ok("1600")), &ok("1653")),
+            "part 2: 1600 -> 1653\n"
+        );
+        assert_eq!(
+            diff_answer(2, Some(&Err("boom".to_string())), &ok("1653")),
+            "part 2: 1653\n"
+        );
+        assert_eq!(
+            diff_answer(1, Some(&ok("1624")), &Err("error: day 1: nope".to_string())),
+            "part 1: failed\nerror: day 1: nope\n"
+        );
+    }
+
+    #[test]
+    fn diff_multi_line_answers() {
+        assert_eq!(
+            diff_answer(
+                2,
+                Some(&Ok("#..\n.#.".to_string())),
+                &Ok("#..\n..#\n###".to_string())
+            ),
+            "part 2: changed\n  #..\n- .#.\n+ ..#\n+ ###\n"
+        );
+    }
+}